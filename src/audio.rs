@@ -1,30 +1,50 @@
 use bevy::prelude::*;
 use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
+use crate::camera::{spectating, SpectatorMode};
 use crate::menu::GameState;
+use crate::world::{GameConfig, SurfaceKind};
+use crate::pause::Paused;
+use crate::player::PlayerEvent;
 
 pub struct AudioPlugin;
 
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), setup_audio)
+        app.init_resource::<AudioSettings>()
+            .add_systems(OnEnter(GameState::InGame), setup_audio)
             .add_systems(Update, (
-                handle_footsteps,
+                (handle_footsteps, handle_player_event_sounds).run_if(not(spectating)),
                 handle_slide_sound,
             ).run_if(in_state(GameState::InGame)));
     }
 }
 
+#[derive(Resource)]
+pub struct AudioSettings {
+    pub master: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self { master: 1.0 }
+    }
+}
+
+type FootstepSamples = HashMap<SurfaceKind, (Arc<Vec<f32>>, Arc<Vec<f32>>)>;
+
 #[derive(Resource)]
 pub struct AudioSystem {
-    _stream: Arc<OutputStream>,
-    stream_handle: Arc<OutputStreamHandle>,
-    footstep_left: Arc<Vec<f32>>,
-    footstep_right: Arc<Vec<f32>>,
+    _stream: Option<Arc<OutputStream>>,
+    stream_handle: Option<Arc<OutputStreamHandle>>,
+    footsteps: FootstepSamples,
+    landing_sound: Arc<Vec<f32>>,
     jump_sound: Arc<Vec<f32>>,
     double_jump_sound: Arc<Vec<f32>>,
     slide_sound: Arc<Vec<f32>>,
+    breath_sound: Arc<Vec<f32>>,
 }
 
 unsafe impl Send for AudioSystem {}
@@ -53,23 +73,40 @@ impl Default for FootstepTimer {
     }
 }
 
-fn setup_audio(mut commands: Commands) {
-    let (stream, stream_handle) = OutputStream::try_default().unwrap();
+fn setup_audio(mut commands: Commands, game_config: Res<GameConfig>) {
+    let (stream, stream_handle) = match OutputStream::try_default() {
+        Ok((stream, stream_handle)) => (Some(Arc::new(stream)), Some(Arc::new(stream_handle))),
+        Err(err) => {
+            warn!("Audio output unavailable, continuing without sound: {err}");
+            (None, None)
+        }
+    };
+    let seed = game_config.seed;
     
-    let footstep_left = generate_footstep_samples(true);
-    let footstep_right = generate_footstep_samples(false);
-    let jump_sound = generate_jump_samples();
-    let double_jump_sound = generate_double_jump_samples();
-    let slide_sound = generate_slide_samples();
+    let footsteps = [SurfaceKind::Checkerboard, SurfaceKind::Platform]
+        .into_iter()
+        .map(|surface| {
+            let salt = 10 + surface as u64 * 2;
+            let left = generate_footstep_samples(true, surface, sub_seed(seed, salt));
+            let right = generate_footstep_samples(false, surface, sub_seed(seed, salt + 1));
+            (surface, (Arc::new(left), Arc::new(right)))
+        })
+        .collect();
+    let landing_sound = generate_landing_samples(sub_seed(seed, 1));
+    let jump_sound = generate_jump_samples(sub_seed(seed, 2));
+    let double_jump_sound = generate_double_jump_samples(sub_seed(seed, 3));
+    let slide_sound = generate_slide_samples(sub_seed(seed, 4));
+    let breath_sound = generate_breath_samples(sub_seed(seed, 5));
     
     commands.insert_resource(AudioSystem {
-        _stream: Arc::new(stream),
-        stream_handle: Arc::new(stream_handle),
-        footstep_left: Arc::new(footstep_left),
-        footstep_right: Arc::new(footstep_right),
+        _stream: stream,
+        stream_handle,
+        footsteps,
+        landing_sound: Arc::new(landing_sound),
         jump_sound: Arc::new(jump_sound),
         double_jump_sound: Arc::new(double_jump_sound),
         slide_sound: Arc::new(slide_sound),
+        breath_sound: Arc::new(breath_sound),
     });
     
     commands.insert_resource(FootstepTimer::default());
@@ -79,26 +116,31 @@ fn setup_audio(mut commands: Commands) {
     });
 }
 
+pub fn sub_seed(seed: u64, salt: u64) -> u64 {
+    let mut x = seed ^ salt.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
 fn handle_footsteps(
     time: Res<Time>,
     keyboard: Res<ButtonInput<KeyCode>>,
     mut timer_res: ResMut<FootstepTimer>,
     audio: Res<AudioSystem>,
-    player_query: Query<(&crate::player::PlayerSpeed, &Transform, &crate::player::JumpState), With<crate::player::Player>>,
+    audio_settings: Res<AudioSettings>,
+    player_query: Query<(&crate::player::PlayerSpeed, &crate::player::Stamina, &crate::player::PlayerMovement), With<crate::player::Player>>,
+    paused: Res<Paused>,
 ) {
-    let Ok((player_speed, transform, jump_state)) = player_query.get_single() else {
+    if paused.0 {
         return;
-    };
+    }
 
-    let is_grounded = transform.translation.y <= 1.01;
+    let Ok((player_speed, stamina, movement)) = player_query.get_single() else {
+        return;
+    };
 
-    if keyboard.just_pressed(KeyCode::Space) {
-        if is_grounded {
-            play_cached_sound(&audio.stream_handle, audio.jump_sound.clone());
-        } else if jump_state.jumps_remaining > 0 {
-            play_cached_sound(&audio.stream_handle, audio.double_jump_sound.clone());
-        }
-    }
+    let is_grounded = movement.is_grounded;
 
     let is_moving = keyboard.pressed(KeyCode::KeyW)
         || keyboard.pressed(KeyCode::KeyS)
@@ -110,34 +152,78 @@ fn handle_footsteps(
         return;
     }
 
-    let speed_factor = (player_speed.current / 8.0).max(0.3);
-    let interval = timer_res.base_interval / speed_factor;
+    let interval = footstep_interval(timer_res.base_interval, player_speed.current, stamina.ratio());
     timer_res.timer.set_duration(Duration::from_secs_f32(interval));
 
     timer_res.timer.tick(time.delta());
 
     if timer_res.timer.just_finished() {
-        let samples = if timer_res.is_left_foot {
-            audio.footstep_left.clone()
-        } else {
-            audio.footstep_right.clone()
-        };
-        play_cached_sound(&audio.stream_handle, samples);
+        let surface = movement.ground_surface.unwrap_or_default();
+        if let Some(samples) = footstep_samples(&audio.footsteps, surface, timer_res.is_left_foot) {
+            play_cached_sound(audio.stream_handle.as_deref(), samples, audio_settings.master);
+        }
+
+        if timer_res.is_left_foot && stamina.ratio() < EXHAUSTED_STAMINA_RATIO {
+            play_cached_sound(audio.stream_handle.as_deref(), audio.breath_sound.clone(), audio_settings.master);
+        }
+
         timer_res.is_left_foot = !timer_res.is_left_foot;
     }
 }
 
-fn play_cached_sound(stream_handle: &OutputStreamHandle, samples: Arc<Vec<f32>>) {
+fn footstep_samples(footsteps: &FootstepSamples, surface: SurfaceKind, is_left_foot: bool) -> Option<Arc<Vec<f32>>> {
+    let (left, right) = footsteps.get(&surface).or_else(|| footsteps.get(&SurfaceKind::default()))?;
+    Some(if is_left_foot { left.clone() } else { right.clone() })
+}
+
+const EXHAUSTED_STAMINA_RATIO: f32 = 0.3;
+const HARD_LANDING_SPEED: f32 = 7.0;
+
+fn handle_player_event_sounds(
+    audio: Res<AudioSystem>,
+    audio_settings: Res<AudioSettings>,
+    mut player_events: EventReader<PlayerEvent>,
+) {
+    for event in player_events.read() {
+        let samples = match *event {
+            PlayerEvent::Jumped { air_jump: false } => &audio.jump_sound,
+            PlayerEvent::Jumped { air_jump: true } => &audio.double_jump_sound,
+            PlayerEvent::Landed { impact_speed } if is_hard_landing(impact_speed) => &audio.landing_sound,
+            _ => continue,
+        };
+
+        play_cached_sound(audio.stream_handle.as_deref(), samples.clone(), audio_settings.master);
+    }
+}
+
+pub fn is_hard_landing(impact_speed: f32) -> bool {
+    impact_speed >= HARD_LANDING_SPEED
+}
+
+pub fn footstep_interval(base_interval: f32, speed: f32, stamina_ratio: f32) -> f32 {
+    let speed_factor = (speed / 8.0).max(0.3);
+    let fatigue_factor = if stamina_ratio < EXHAUSTED_STAMINA_RATIO { 0.85 } else { 1.0 };
+    base_interval / speed_factor * fatigue_factor
+}
+
+fn play_cached_sound(stream_handle: Option<&OutputStreamHandle>, samples: Arc<Vec<f32>>, volume: f32) -> bool {
+    let Some(stream_handle) = stream_handle.filter(|_| volume > 0.0) else {
+        return false;
+    };
+
     let sound = CachedSound {
         sample_rate: 44100,
         samples,
         current_sample: 0,
     };
     
-    if let Ok(sink) = Sink::try_new(stream_handle) {
-        sink.append(sound);
-        sink.detach();
-    }
+    let Ok(sink) = Sink::try_new(stream_handle) else {
+        return false;
+    };
+    sink.set_volume(volume);
+    sink.append(sound);
+    sink.detach();
+    true
 }
 
 struct CachedSound {
@@ -180,21 +266,26 @@ impl Source for CachedSound {
     }
 }
 
-fn generate_footstep_samples(is_left: bool) -> Vec<f32> {
+pub fn generate_footstep_samples(is_left: bool, surface: SurfaceKind, seed: u64) -> Vec<f32> {
+    let (cutoff_scale, decay_scale, surface_gain) = match surface {
+        SurfaceKind::Checkerboard => (1.0, 1.0, 1.0),
+        SurfaceKind::Platform => (2.2, 1.4, 0.8),
+    };
+
     let sample_rate = 44100;
     let attack = 0.005;
-    let decay = if is_left { 0.08 } else { 0.06 };
+    let decay = if is_left { 0.08 } else { 0.06 } * decay_scale;
     let duration = attack + decay;
     let num_samples = (sample_rate as f32 * duration) as usize;
     
-    let lpf_cutoff = if is_left { 800.0 } else { 650.0 };
-    let gain = if is_left { 0.8 } else { 0.6 };
+    let lpf_cutoff = if is_left { 800.0 } else { 650.0 } * cutoff_scale;
+    let gain = if is_left { 0.8 } else { 0.6 } * surface_gain;
     let pan = if is_left { 0.45 } else { 0.55 };
     
     let mut samples = Vec::with_capacity(num_samples * 2);
     
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
     
     let mut lpf_state = 0.0;
     let lpf_alpha = 1.0 - (-2.0 * std::f32::consts::PI * lpf_cutoff / sample_rate as f32).exp();
@@ -233,15 +324,54 @@ fn generate_footstep_samples(is_left: bool) -> Vec<f32> {
     samples
 }
 
-fn generate_jump_samples() -> Vec<f32> {
+pub fn generate_landing_samples(seed: u64) -> Vec<f32> {
+    let sample_rate = 44100;
+    let attack = 0.003;
+    let decay = 0.18;
+    let duration = attack + decay;
+    let num_samples = (sample_rate as f32 * duration) as usize;
+    
+    let mut samples = Vec::with_capacity(num_samples * 2);
+    
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    
+    let mut lpf_state = 0.0;
+    let lpf_alpha = 1.0 - (-2.0 * std::f32::consts::PI * 350.0 / sample_rate as f32).exp();
+    
+    for i in 0..num_samples {
+        let t = i as f32 / sample_rate as f32;
+        
+        let envelope = if t < attack {
+            t / attack
+        } else {
+            (1.0 - (t - attack) / decay).max(0.0).powf(2.0)
+        };
+        
+        let thump = (2.0 * std::f32::consts::PI * 70.0 * t).sin();
+        
+        let white_noise = rng.r#gen::<f32>() * 2.0 - 1.0;
+        
+        lpf_state += lpf_alpha * (white_noise - lpf_state);
+        
+        let sample = (thump * 0.5 + lpf_state * 0.5) * envelope * 0.6;
+        
+        samples.push(sample);
+        samples.push(sample);
+    }
+    
+    samples
+}
+
+pub fn generate_jump_samples(seed: u64) -> Vec<f32> {
     let sample_rate = 44100;
     let duration = 0.15;
     let num_samples = (sample_rate as f32 * duration) as usize;
     
     let mut samples = Vec::with_capacity(num_samples * 2);
     
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
     
     let mut lpf_state = 0.0;
     let lpf_alpha = 1.0 - (-2.0 * std::f32::consts::PI * 1200.0 / sample_rate as f32).exp();
@@ -266,15 +396,15 @@ fn generate_jump_samples() -> Vec<f32> {
     
     samples
 }
-fn generate_double_jump_samples() -> Vec<f32> {
+pub fn generate_double_jump_samples(seed: u64) -> Vec<f32> {
     let sample_rate = 44100;
     let duration = 0.12;
     let num_samples = (sample_rate as f32 * duration) as usize;
 
     let mut samples = Vec::with_capacity(num_samples * 2);
 
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
 
     let mut lpf_state = 0.0;
     let lpf_alpha = 1.0 - (-2.0 * std::f32::consts::PI * 2000.0 / sample_rate as f32).exp();
@@ -303,8 +433,10 @@ fn generate_double_jump_samples() -> Vec<f32> {
 fn handle_slide_sound(
     keyboard: Res<ButtonInput<KeyCode>>,
     audio: Res<AudioSystem>,
+    audio_settings: Res<AudioSettings>,
     mut slide_res: ResMut<SlideSound>,
     player_query: Query<(&crate::player::PlayerMovement, &Transform), With<crate::player::Player>>,
+    spectator: Res<SpectatorMode>,
 ) {
     let Ok((movement, transform)) = player_query.get_single() else {
         return;
@@ -314,7 +446,7 @@ fn handle_slide_sound(
     let is_braking = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
     let has_velocity = movement.velocity.length() > 1.0;
 
-    let should_play = is_braking && is_grounded && has_velocity;
+    let should_play = is_braking && is_grounded && has_velocity && !spectator.active;
 
     if should_play && !slide_res.is_playing {
         let sound = LoopingSound {
@@ -323,8 +455,13 @@ fn handle_slide_sound(
             current_sample: 0,
         };
         
-        if let Ok(sink) = Sink::try_new(&audio.stream_handle) {
-            sink.set_volume(0.4);
+        let sink = audio
+            .stream_handle
+            .as_deref()
+            .filter(|_| audio_settings.master > 0.0)
+            .and_then(|handle| Sink::try_new(handle).ok());
+        if let Some(sink) = sink {
+            sink.set_volume(0.4 * audio_settings.master);
             sink.append(sound);
             slide_res.sink = Some(sink);
             slide_res.is_playing = true;
@@ -335,6 +472,10 @@ fn handle_slide_sound(
         }
         slide_res.is_playing = false;
     }
+
+    if let Some(sink) = &slide_res.sink {
+        sink.set_volume(0.4 * audio_settings.master);
+    }
 }
 
 struct LoopingSound {
@@ -371,15 +512,15 @@ impl Source for LoopingSound {
     }
 }
 
-fn generate_slide_samples() -> Vec<f32> {
+pub fn generate_slide_samples(seed: u64) -> Vec<f32> {
     let sample_rate = 44100;
     let duration = 1.0;
     let num_samples = (sample_rate as f32 * duration) as usize;
     
     let mut samples = Vec::with_capacity(num_samples * 2);
     
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
     
     let mut lpf_state = 0.0;
     let lpf_cutoff = 400.0;
@@ -410,3 +551,185 @@ fn generate_slide_samples() -> Vec<f32> {
     
     samples
 }
+
+pub fn generate_breath_samples(seed: u64) -> Vec<f32> {
+    let sample_rate = 44100;
+    let duration = 0.45;
+    let num_samples = (sample_rate as f32 * duration) as usize;
+    
+    let mut samples = Vec::with_capacity(num_samples * 2);
+    
+    use rand::{Rng, SeedableRng};
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    
+    let mut lpf_state = 0.0;
+    let lpf_alpha = 1.0 - (-2.0 * std::f32::consts::PI * 1800.0 / sample_rate as f32).exp();
+    
+    let mut hpf_state = 0.0;
+    let hpf_alpha = 1.0 - (-2.0 * std::f32::consts::PI * 300.0 / sample_rate as f32).exp();
+    
+    for i in 0..num_samples {
+        let t = i as f32 / sample_rate as f32;
+        
+        let white_noise = rng.r#gen::<f32>() * 2.0 - 1.0;
+        
+        lpf_state += lpf_alpha * (white_noise - lpf_state);
+        
+        let hpf_input = lpf_state;
+        hpf_state += hpf_alpha * (hpf_input - hpf_state);
+        let filtered = hpf_input - hpf_state;
+        
+        let envelope = (std::f32::consts::PI * t / duration).sin().powf(2.0);
+        
+        let sample = filtered * envelope * 0.2;
+        
+        samples.push(sample);
+        samples.push(sample);
+    }
+    
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::player::{Player, PlayerMovement, PlayerSpeed, Stamina};
+
+    fn disabled_audio(footsteps: FootstepSamples) -> AudioSystem {
+        let silence = Arc::new(Vec::new());
+        AudioSystem {
+            _stream: None,
+            stream_handle: None,
+            footsteps,
+            landing_sound: silence.clone(),
+            jump_sound: silence.clone(),
+            double_jump_sound: silence.clone(),
+            slide_sound: silence.clone(),
+            breath_sound: silence,
+        }
+    }
+
+    fn walking_world(audio: AudioSystem) -> World {
+        let mut world = World::new();
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs(1));
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        keyboard.press(KeyCode::KeyW);
+
+        world.insert_resource(time);
+        world.insert_resource(keyboard);
+        world.insert_resource(FootstepTimer::default());
+        world.insert_resource(audio);
+        world.insert_resource(AudioSettings::default());
+        world.insert_resource(Paused(false));
+        world.spawn((
+            Player,
+            PlayerSpeed { current: 8.0, min: 4.0, max: 12.0 },
+            Stamina { current: 10.0, ..default() },
+            PlayerMovement {
+                velocity: Vec3::ZERO,
+                drift_factor: 0.0,
+                is_braking: false,
+                is_grounded: true,
+                ground_surface: Some(SurfaceKind::Platform),
+                fall_speed: 0.0,
+            },
+        ));
+        world
+    }
+
+    #[test]
+    fn footsteps_are_a_no_op_without_an_audio_device() {
+        let mut world = walking_world(disabled_audio(HashMap::new()));
+        world.run_system_once(handle_footsteps).unwrap();
+
+        assert!(!world.resource::<FootstepTimer>().is_left_foot);
+    }
+
+    #[test]
+    fn nothing_plays_without_a_stream_or_when_muted() {
+        let samples = Arc::new(vec![0.5; 4]);
+        assert!(!play_cached_sound(None, samples.clone(), 1.0));
+        assert!(!play_cached_sound(None, samples, 0.0));
+
+        let mut world = walking_world(disabled_audio(HashMap::new()));
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        keyboard.press(KeyCode::ShiftLeft);
+        world.insert_resource(keyboard);
+        world.insert_resource(SlideSound { sink: None, is_playing: false });
+        world.insert_resource(SpectatorMode::default());
+        let player = world.query_filtered::<Entity, With<Player>>().single(&world);
+        world.entity_mut(player).insert(Transform::from_xyz(0.0, 1.0, 0.0));
+        world.get_mut::<PlayerMovement>(player).unwrap().velocity = Vec3::new(0.0, 0.0, -10.0);
+
+        world.run_system_once(handle_slide_sound).unwrap();
+
+        let slide = world.resource::<SlideSound>();
+        assert!(slide.sink.is_none());
+        assert!(!slide.is_playing);
+    }
+
+    #[test]
+    fn missing_surface_samples_fall_back_to_the_default_surface() {
+        let left = Arc::new(vec![1.0]);
+        let right = Arc::new(vec![2.0]);
+        let footsteps: FootstepSamples = [(SurfaceKind::default(), (left.clone(), right.clone()))].into_iter().collect();
+
+        assert_eq!(footstep_samples(&footsteps, SurfaceKind::Platform, true), Some(left));
+        assert_eq!(footstep_samples(&footsteps, SurfaceKind::Platform, false), Some(right));
+        assert_eq!(footstep_samples(&HashMap::new(), SurfaceKind::Platform, true), None);
+    }
+
+    #[test]
+    fn only_fast_landings_are_hard() {
+        assert!(!is_hard_landing(HARD_LANDING_SPEED - 0.1));
+        assert!(is_hard_landing(HARD_LANDING_SPEED));
+    }
+
+    #[test]
+    fn footsteps_quicken_with_speed_and_fatigue() {
+        let walking = footstep_interval(0.4, 8.0, 1.0);
+        assert!(footstep_interval(0.4, 12.0, 1.0) < walking);
+        assert!(footstep_interval(0.4, 8.0, 0.1) < walking);
+        assert_eq!(footstep_interval(0.4, 0.0, 1.0), footstep_interval(0.4, 1.0, 1.0));
+    }
+
+    #[test]
+    fn footstep_cadence_tracks_stamina() {
+        let mut world = walking_world(disabled_audio(HashMap::new()));
+        world.run_system_once(handle_footsteps).unwrap();
+        let tired = world.resource::<FootstepTimer>().timer.duration();
+
+        world.query::<&mut Stamina>().single_mut(&mut world).current = 100.0;
+        world.run_system_once(handle_footsteps).unwrap();
+        let rested = world.resource::<FootstepTimer>().timer.duration();
+
+        assert_eq!(tired, Duration::from_secs_f32(footstep_interval(0.4, 8.0, 0.1)));
+        assert!(tired < rested);
+    }
+
+    #[test]
+    fn surfaces_sound_different() {
+        let checkerboard = generate_footstep_samples(true, SurfaceKind::Checkerboard, 7);
+        let platform = generate_footstep_samples(true, SurfaceKind::Platform, 7);
+
+        assert!(!checkerboard.is_empty() && !platform.is_empty());
+        assert_ne!(checkerboard.len(), platform.len());
+        assert_ne!(checkerboard, platform);
+    }
+
+    #[test]
+    fn sounds_are_reproducible_per_seed() {
+        assert_eq!(sub_seed(9, 1), sub_seed(9, 1));
+        assert_ne!(sub_seed(9, 1), sub_seed(9, 2));
+        assert_ne!(sub_seed(9, 1), sub_seed(10, 1));
+
+        assert_eq!(generate_landing_samples(sub_seed(9, 1)), generate_landing_samples(sub_seed(9, 1)));
+        assert_ne!(generate_landing_samples(sub_seed(9, 1)), generate_landing_samples(sub_seed(10, 1)));
+        assert_eq!(
+            generate_footstep_samples(false, SurfaceKind::Checkerboard, 3),
+            generate_footstep_samples(false, SurfaceKind::Checkerboard, 3),
+        );
+    }
+}
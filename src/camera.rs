@@ -1,19 +1,26 @@
 use bevy::prelude::*;
+use bevy::input::mouse::{MouseMotion, MouseWheel};
 use bevy::window::CursorGrabMode;
-use crate::player::Player;
+use bevy_rapier3d::prelude::*;
+use crate::player::{Player, PlayerDimensions, PlayerMovement, PlayerSpeed};
 use crate::physics::GameSystemSet;
 use crate::menu::GameState;
+use crate::pause::Paused;
+use crate::settings::{AccessibilitySettings, Settings, MIN_FOV, MAX_FOV};
+use crate::world::SpawnPoints;
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), (spawn_camera, grab_cursor_on_start))
-            .add_systems(OnExit(GameState::InGame), release_cursor_on_exit)
+        app.init_resource::<SpectatorMode>()
+            .add_systems(OnEnter(GameState::InGame), (spawn_camera, grab_cursor_on_start))
+            .add_systems(OnExit(GameState::InGame), (release_cursor_on_exit, despawn_camera, reset_spectator))
+            .add_systems(Update, toggle_spectator.in_set(GameSystemSet::Input).run_if(in_state(GameState::InGame)))
             .add_systems(Update, (
-                toggle_cursor_grab,
                 handle_window_focus,
-                first_person_camera,
+                first_person_camera.run_if(not(spectating)),
+                spectator_camera.run_if(spectating),
             ).in_set(GameSystemSet::Camera).run_if(in_state(GameState::InGame)));
     }
 }
@@ -25,6 +32,20 @@ pub struct FirstPersonCamera {
     pub target_pitch: f32,
     pub target_yaw: f32,
     pub sensitivity: f32,
+    pub raw_input: bool,
+    pub smoothing: f32,
+    pub bob_amplitude: f32,
+    pub bob_frequency: f32,
+    pub bob_distance: f32,
+    pub bob_weight: f32,
+    pub base_fov: f32,
+    pub fov: f32,
+    pub target_fov: f32,
+    pub fov_punch: f32,
+    pub fov_kick_enabled: bool,
+    pub shake_enabled: bool,
+    pub shake_amplitude: f32,
+    pub shake_time: f32,
 }
 
 impl Default for FirstPersonCamera {
@@ -35,14 +56,76 @@ impl Default for FirstPersonCamera {
             target_pitch: 0.0,
             target_yaw: 0.0,
             sensitivity: 0.002,
+            raw_input: false,
+            smoothing: BASE_CAMERA_SMOOTHING,
+            bob_amplitude: 0.04,
+            bob_frequency: 1.6,
+            bob_distance: 0.0,
+            bob_weight: 0.0,
+            base_fov: 45.0,
+            fov: 45.0,
+            target_fov: 45.0,
+            fov_punch: 15.0,
+            fov_kick_enabled: true,
+            shake_enabled: true,
+            shake_amplitude: 0.03,
+            shake_time: 0.0,
         }
     }
 }
 
 #[derive(Resource)]
-struct CursorGrabbed(bool);
+pub struct CursorGrabbed(pub bool);
 
-fn spawn_camera(mut commands: Commands) {
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct SpectatorMode {
+    pub active: bool,
+    pub speed: f32,
+    pub min_speed: f32,
+    pub max_speed: f32,
+}
+
+impl Default for SpectatorMode {
+    fn default() -> Self {
+        Self {
+            active: false,
+            speed: 15.0,
+            min_speed: 2.0,
+            max_speed: 80.0,
+        }
+    }
+}
+
+pub fn spectating(spectator: Res<SpectatorMode>) -> bool {
+    spectator.active
+}
+
+const SPECTATOR_GROUND_PROBE: f32 = 200.0;
+const SHAKE_SPEED_THRESHOLD: f32 = 0.5;
+const BASE_CAMERA_SMOOTHING: f32 = 100.0;
+const INSTANT_CAMERA_SMOOTHING: f32 = 1000.0;
+const BOB_REFERENCE_SPEED: f32 = 8.0;
+const BOB_DRIFT_CUTOFF: f32 = 0.5;
+
+pub fn camera_smoothing(base: f32, drift_factor: f32, accessibility: &AccessibilitySettings) -> f32 {
+    if accessibility.reduce_motion {
+        INSTANT_CAMERA_SMOOTHING
+    } else if accessibility.drift_smoothing() {
+        base * (1.0 - drift_factor.clamp(0.0, 1.0) * 0.4)
+    } else {
+        base
+    }
+}
+
+pub fn look_lerp_factor(raw_input: bool, smoothing: f32, delta_time: f32) -> f32 {
+    if raw_input {
+        1.0
+    } else {
+        (smoothing * delta_time).min(1.0)
+    }
+}
+
+fn spawn_camera(mut commands: Commands, settings: Res<Settings>) {
     commands.insert_resource(CursorGrabbed(true));
     
     commands.spawn((
@@ -51,8 +134,19 @@ fn spawn_camera(mut commands: Commands) {
             clear_color: ClearColorConfig::None,
             ..default()
         },
+        Projection::Perspective(PerspectiveProjection {
+            fov: settings.fov.to_radians(),
+            ..default()
+        }),
         Transform::from_xyz(0.0, 1.6, 0.0),
-        FirstPersonCamera::default(),
+        FirstPersonCamera {
+            sensitivity: settings.sensitivity,
+            raw_input: settings.raw_mouse_input,
+            base_fov: settings.fov,
+            fov: settings.fov,
+            target_fov: settings.fov,
+            ..default()
+        },
         DistanceFog {
             color: Color::srgb(0.35, 0.48, 0.66),
             falloff: FogFalloff::Linear {
@@ -99,47 +193,137 @@ fn handle_window_focus(
     }
 }
 
-fn toggle_cursor_grab(
+fn reset_spectator(mut spectator: ResMut<SpectatorMode>) {
+    spectator.active = false;
+}
+
+pub fn spectator_landing(camera_position: Vec3, ground_distance: Option<f32>, dimensions: &PlayerDimensions) -> Option<Vec3> {
+    ground_distance.map(|distance| {
+        camera_position - Vec3::Y * distance + Vec3::Y * (dimensions.half_height + dimensions.radius + 0.05)
+    })
+}
+
+type SpectatorBody = (
+    Entity,
+    &'static mut Transform,
+    &'static mut Velocity,
+    &'static mut RigidBody,
+    &'static mut PlayerMovement,
+    &'static PlayerDimensions,
+);
+
+fn toggle_spectator(
     keyboard: Res<ButtonInput<KeyCode>>,
-    mut windows: Query<&mut Window>,
-    mut cursor_grabbed: ResMut<CursorGrabbed>,
+    mut spectator: ResMut<SpectatorMode>,
+    mut player_query: Query<SpectatorBody, With<Player>>,
+    camera_query: Query<&Transform, (With<FirstPersonCamera>, Without<Player>)>,
+    rapier_context: ReadRapierContext,
+    spawn_points: Res<SpawnPoints>,
+    paused: Res<Paused>,
 ) {
-    if keyboard.just_pressed(KeyCode::Escape) {
-        for mut window in windows.iter_mut() {
-            if !window.focused {
-                continue;
-            }
-            
-            match window.cursor_options.grab_mode {
-                CursorGrabMode::Locked => {
-                    window.cursor_options.grab_mode = CursorGrabMode::None;
-                    window.cursor_options.visible = true;
-                    cursor_grabbed.0 = false;
-                }
-                _ => {
-                    window.cursor_options.grab_mode = CursorGrabMode::Locked;
-                    window.cursor_options.visible = false;
-                    cursor_grabbed.0 = true;
-                }
-            }
+    if paused.0 || !keyboard.just_pressed(KeyCode::KeyN) {
+        return;
+    }
+
+    let Ok((player_entity, mut player_transform, mut velocity, mut body, mut movement, dimensions)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    spectator.active = !spectator.active;
+    velocity.linvel = Vec3::ZERO;
+    velocity.angvel = Vec3::ZERO;
+    movement.velocity = Vec3::ZERO;
+    movement.is_grounded = false;
+    movement.fall_speed = 0.0;
+
+    if spectator.active {
+        *body = RigidBody::Fixed;
+        return;
+    }
+
+    if let Ok(camera_transform) = camera_query.get_single() {
+        let rapier_context = rapier_context.single();
+        let filter = QueryFilter::default().exclude_rigid_body(player_entity);
+        let ground_distance = rapier_context
+            .cast_ray(camera_transform.translation, Vec3::NEG_Y, SPECTATOR_GROUND_PROBE, true, filter)
+            .map(|(_, distance)| distance);
+
+        let landing = spectator_landing(camera_transform.translation, ground_distance, dimensions)
+            .or_else(|| spawn_points.nearest(camera_transform.translation));
+        if let Some(landing) = landing {
+            player_transform.translation = landing;
         }
     }
+
+    *body = RigidBody::Dynamic;
 }
 
-fn first_person_camera(
-    player_query: Query<&Transform, With<Player>>,
-    mut camera_query: Query<(&mut Transform, &mut FirstPersonCamera), (With<Camera3d>, Without<Player>)>,
-    mut motion_events: EventReader<bevy::input::mouse::MouseMotion>,
+fn spectator_camera(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut spectator: ResMut<SpectatorMode>,
+    mut scroll_events: EventReader<MouseWheel>,
+    mut motion_events: EventReader<MouseMotion>,
+    mut camera_query: Query<(&mut Transform, &mut FirstPersonCamera)>,
     time: Res<Time>,
+    paused: Res<Paused>,
 ) {
-    let Ok(player_transform) = player_query.get_single() else {
+    if paused.0 {
+        scroll_events.clear();
+        motion_events.clear();
         return;
-    };
+    }
 
     let Ok((mut camera_transform, mut fps_camera)) = camera_query.get_single_mut() else {
         return;
     };
 
+    for event in scroll_events.read() {
+        spectator.speed = (spectator.speed + event.y * 2.0).clamp(spectator.min_speed, spectator.max_speed);
+    }
+
+    let delta_time = time.delta_secs().min(0.1);
+    let smoothing = fps_camera.smoothing;
+    apply_mouse_look(&mut fps_camera, &mut motion_events, smoothing, delta_time);
+
+    camera_transform.rotation = Quat::from_euler(
+        EulerRot::YXZ,
+        fps_camera.yaw,
+        fps_camera.pitch,
+        0.0,
+    );
+
+    let forward = camera_transform.rotation * Vec3::NEG_Z;
+    let right = camera_transform.rotation * Vec3::X;
+    let mut direction = Vec3::ZERO;
+
+    if keyboard.pressed(KeyCode::KeyW) {
+        direction += forward;
+    }
+    if keyboard.pressed(KeyCode::KeyS) {
+        direction -= forward;
+    }
+    if keyboard.pressed(KeyCode::KeyA) {
+        direction -= right;
+    }
+    if keyboard.pressed(KeyCode::KeyD) {
+        direction += right;
+    }
+    if keyboard.pressed(KeyCode::Space) {
+        direction += Vec3::Y;
+    }
+    if keyboard.pressed(KeyCode::ControlLeft) {
+        direction -= Vec3::Y;
+    }
+
+    camera_transform.translation += direction.normalize_or_zero() * spectator.speed * delta_time;
+}
+
+fn apply_mouse_look(
+    fps_camera: &mut FirstPersonCamera,
+    motion_events: &mut EventReader<MouseMotion>,
+    smoothing: f32,
+    delta_time: f32,
+) {
     let mut delta_yaw = 0.0;
     let mut delta_pitch = 0.0;
 
@@ -151,10 +335,7 @@ fn first_person_camera(
     fps_camera.target_yaw += delta_yaw;
     fps_camera.target_pitch = (fps_camera.target_pitch + delta_pitch).clamp(-1.54, 1.54);
 
-    let smoothing = 100.0;
-
-    let delta_time = time.delta_secs().min(0.1);
-    let lerp_factor = (smoothing * delta_time).min(1.0);
+    let lerp_factor = look_lerp_factor(fps_camera.raw_input, smoothing, delta_time);
 
     fps_camera.yaw += (fps_camera.target_yaw - fps_camera.yaw) * lerp_factor;
     fps_camera.pitch += (fps_camera.target_pitch - fps_camera.pitch) * lerp_factor;
@@ -167,9 +348,75 @@ fn first_person_camera(
         fps_camera.pitch = 0.0;
         fps_camera.target_pitch = 0.0;
     }
+}
 
-    let eye_height = 1.6;
-    camera_transform.translation = player_transform.translation + Vec3::new(0.0, eye_height, 0.0);
+fn despawn_camera(
+    mut commands: Commands,
+    camera_query: Query<Entity, With<FirstPersonCamera>>,
+) {
+    for entity in &camera_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn first_person_camera(
+    player_query: Query<(&Transform, &PlayerMovement, &PlayerSpeed, &PlayerDimensions), With<Player>>,
+    mut camera_query: Query<(&mut Transform, &mut FirstPersonCamera, &mut Projection), (With<Camera3d>, Without<Player>)>,
+    mut motion_events: EventReader<MouseMotion>,
+    time: Res<Time>,
+    paused: Res<Paused>,
+    accessibility: Res<AccessibilitySettings>,
+) {
+    if paused.0 {
+        motion_events.clear();
+        return;
+    }
+
+    let Ok((player_transform, movement, speed, dimensions)) = player_query.get_single() else {
+        return;
+    };
+
+    let Ok((mut camera_transform, mut fps_camera, mut projection)) = camera_query.get_single_mut() else {
+        return;
+    };
+
+    let delta_time = time.delta_secs().min(0.1);
+    let smoothing = camera_smoothing(fps_camera.smoothing, movement.drift_factor, &accessibility);
+    apply_mouse_look(&mut fps_camera, &mut motion_events, smoothing, delta_time);
+
+    let horizontal_speed = Vec2::new(movement.velocity.x, movement.velocity.z).length();
+    let bob_target = if accessibility.head_bob() {
+        head_bob_target(horizontal_speed, movement.drift_factor, movement.is_grounded)
+    } else {
+        0.0
+    };
+
+    fps_camera.bob_distance += horizontal_speed * delta_time;
+    fps_camera.bob_weight += (bob_target - fps_camera.bob_weight) * (10.0 * delta_time).min(1.0);
+    if !accessibility.head_bob() {
+        fps_camera.bob_weight = 0.0;
+    }
+
+    let bob = head_bob_offset(
+        fps_camera.bob_distance,
+        fps_camera.bob_amplitude,
+        fps_camera.bob_frequency,
+        fps_camera.bob_weight,
+    );
+
+    let speed_ratio = if speed.max > 0.0 { horizontal_speed / speed.max } else { 0.0 };
+    let shake_strength = if fps_camera.shake_enabled && accessibility.shake() && movement.is_grounded {
+        shake_amplitude(speed_ratio, SHAKE_SPEED_THRESHOLD, fps_camera.shake_amplitude)
+    } else {
+        0.0
+    };
+    fps_camera.shake_time += delta_time;
+    let shake = shake_offset(fps_camera.shake_time, shake_strength);
+
+    let yaw_rotation = Quat::from_rotation_y(fps_camera.yaw);
+    camera_transform.translation = player_transform.translation
+        + Vec3::new(0.0, dimensions.eye_offset, 0.0)
+        + yaw_rotation * (bob + shake);
 
     camera_transform.rotation = Quat::from_euler(
         EulerRot::YXZ,
@@ -177,4 +424,173 @@ fn first_person_camera(
         fps_camera.pitch,
         0.0,
     );
+
+    fps_camera.target_fov = if fps_camera.fov_kick_enabled && accessibility.fov_kick() {
+        speed_to_fov(
+            horizontal_speed,
+            speed.max,
+            fps_camera.base_fov,
+            fps_camera.base_fov + fps_camera.fov_punch,
+        )
+    } else {
+        fps_camera.base_fov
+    };
+
+    let fov_lerp = (6.0 * delta_time).min(1.0);
+    fps_camera.fov = approach_fov(fps_camera.fov, fps_camera.target_fov, fov_lerp);
+
+    if let Projection::Perspective(perspective) = projection.as_mut() {
+        perspective.fov = fps_camera.fov.to_radians();
+    }
+}
+
+pub fn speed_to_fov(speed: f32, max_speed: f32, base_fov: f32, max_fov: f32) -> f32 {
+    let t = if max_speed > 0.0 {
+        (speed / max_speed).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (base_fov + (max_fov - base_fov) * t).clamp(MIN_FOV, MAX_FOV)
+}
+
+pub fn approach_fov(current: f32, target: f32, lerp_factor: f32) -> f32 {
+    let target = target.clamp(MIN_FOV, MAX_FOV);
+    let fov = current + (target - current) * lerp_factor.clamp(0.0, 1.0);
+    fov.clamp(MIN_FOV, MAX_FOV)
+}
+
+pub fn shake_amplitude(speed_ratio: f32, threshold: f32, max_amplitude: f32) -> f32 {
+    if speed_ratio <= threshold || threshold >= 1.0 {
+        return 0.0;
+    }
+
+    let t = ((speed_ratio - threshold) / (1.0 - threshold)).clamp(0.0, 1.0);
+    max_amplitude * t
+}
+
+pub fn shake_offset(time: f32, amplitude: f32) -> Vec3 {
+    if amplitude <= 0.0 {
+        return Vec3::ZERO;
+    }
+
+    Vec3::new(
+        (time * 37.0).sin() * (time * 13.0).cos() * amplitude,
+        (time * 29.0).cos() * (time * 17.0).sin() * amplitude,
+        0.0,
+    )
+}
+
+pub fn head_bob_target(horizontal_speed: f32, drift_factor: f32, is_grounded: bool) -> f32 {
+    if is_grounded && drift_factor < BOB_DRIFT_CUTOFF {
+        (horizontal_speed / BOB_REFERENCE_SPEED).min(1.0)
+    } else {
+        0.0
+    }
+}
+
+pub fn head_bob_offset(distance: f32, amplitude: f32, frequency: f32, weight: f32) -> Vec3 {
+    if weight <= 0.0 || amplitude <= 0.0 {
+        return Vec3::ZERO;
+    }
+
+    let phase = distance * frequency * std::f32::consts::PI;
+    let vertical = phase.sin().abs() * amplitude * weight;
+    let lateral = phase.cos() * amplitude * 0.5 * weight;
+
+    Vec3::new(lateral, vertical, 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_bob_is_still_when_standing() {
+        let weight = head_bob_target(0.0, 0.0, true);
+        assert_eq!(weight, 0.0);
+        assert_eq!(head_bob_offset(3.7, 0.04, 1.6, weight), Vec3::ZERO);
+    }
+
+    #[test]
+    fn head_bob_follows_grounded_speed() {
+        assert!((head_bob_target(4.0, 0.0, true) - 0.5).abs() < 1e-6);
+        assert_eq!(head_bob_target(30.0, 0.0, true), 1.0);
+        assert_eq!(head_bob_target(8.0, 0.0, false), 0.0);
+        assert_eq!(head_bob_target(8.0, 0.8, true), 0.0);
+
+        let offset = head_bob_offset(0.25, 0.04, 1.6, 1.0);
+        assert!(offset.y > 0.0 && offset.y <= 0.04);
+        assert!(offset.x.abs() <= 0.02);
+    }
+
+    #[test]
+    fn fov_approaches_target_within_limits() {
+        let fov = approach_fov(45.0, 60.0, 0.5);
+        assert!((fov - 52.5).abs() < 1e-4);
+        assert_eq!(approach_fov(45.0, 60.0, 1.0), 60.0);
+        assert_eq!(approach_fov(45.0, 60.0, 5.0), 60.0);
+        assert_eq!(approach_fov(45.0, 500.0, 1.0), MAX_FOV);
+        assert_eq!(approach_fov(MIN_FOV, 0.0, 1.0), MIN_FOV);
+
+        let mut current = 45.0;
+        for _ in 0..200 {
+            current = approach_fov(current, 60.0, 0.1);
+        }
+        assert!((current - 60.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn fov_scales_with_speed_and_clamps() {
+        assert_eq!(speed_to_fov(0.0, 20.0, 45.0, 60.0), 45.0);
+        assert!((speed_to_fov(10.0, 20.0, 45.0, 60.0) - 52.5).abs() < 1e-4);
+        assert_eq!(speed_to_fov(20.0, 20.0, 45.0, 60.0), 60.0);
+        assert_eq!(speed_to_fov(80.0, 20.0, 45.0, 60.0), 60.0);
+        assert_eq!(speed_to_fov(10.0, 0.0, 45.0, 60.0), 45.0);
+        assert_eq!(speed_to_fov(20.0, 20.0, 100.0, 130.0), MAX_FOV);
+    }
+
+    #[test]
+    fn spectator_lands_with_feet_on_the_ground() {
+        let dimensions = PlayerDimensions::default();
+        let landing = spectator_landing(Vec3::new(3.0, 20.0, -4.0), Some(15.0), &dimensions).unwrap();
+        let feet = landing.y - dimensions.half_height - dimensions.radius;
+
+        assert_eq!(landing.xz(), Vec2::new(3.0, -4.0));
+        assert!((feet - 5.05).abs() < 1e-5);
+        assert_eq!(spectator_landing(Vec3::ZERO, None, &dimensions), None);
+    }
+
+    #[test]
+    fn shake_only_kicks_in_above_the_threshold() {
+        assert_eq!(shake_amplitude(0.3, SHAKE_SPEED_THRESHOLD, 0.03), 0.0);
+        assert_eq!(shake_amplitude(SHAKE_SPEED_THRESHOLD, SHAKE_SPEED_THRESHOLD, 0.03), 0.0);
+
+        let mild = shake_amplitude(0.7, SHAKE_SPEED_THRESHOLD, 0.03);
+        let strong = shake_amplitude(0.9, SHAKE_SPEED_THRESHOLD, 0.03);
+        assert!(0.0 < mild && mild < strong);
+        assert_eq!(shake_amplitude(2.0, SHAKE_SPEED_THRESHOLD, 0.03), 0.03);
+        assert_eq!(shake_offset(1.0, 0.0), Vec3::ZERO);
+    }
+
+    #[test]
+    fn reduce_motion_ignores_drift_and_disables_effects() {
+        let reduced = AccessibilitySettings { reduce_motion: true, ..default() };
+        assert_eq!(camera_smoothing(BASE_CAMERA_SMOOTHING, 0.0, &reduced), INSTANT_CAMERA_SMOOTHING);
+        assert_eq!(camera_smoothing(BASE_CAMERA_SMOOTHING, 1.0, &reduced), INSTANT_CAMERA_SMOOTHING);
+        assert!(!reduced.head_bob() && !reduced.shake() && !reduced.fov_kick() && !reduced.drift_smoothing());
+
+        let normal = AccessibilitySettings::default();
+        assert!(camera_smoothing(BASE_CAMERA_SMOOTHING, 1.0, &normal) < BASE_CAMERA_SMOOTHING);
+
+        let no_drift = AccessibilitySettings { disable_drift_smoothing: true, ..default() };
+        assert_eq!(camera_smoothing(BASE_CAMERA_SMOOTHING, 1.0, &no_drift), BASE_CAMERA_SMOOTHING);
+        assert!(no_drift.shake() && no_drift.head_bob());
+    }
+
+    #[test]
+    fn raw_input_skips_look_smoothing() {
+        assert_eq!(look_lerp_factor(true, BASE_CAMERA_SMOOTHING, 0.001), 1.0);
+        assert!((look_lerp_factor(false, BASE_CAMERA_SMOOTHING, 0.001) - 0.1).abs() < 1e-6);
+        assert_eq!(look_lerp_factor(false, BASE_CAMERA_SMOOTHING, 0.5), 1.0);
+    }
 }
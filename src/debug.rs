@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use crate::player::{Player, PlayerSpeed, PlayerMovement};
+use crate::drawing::DrawTarget;
 use crate::menu::GameState;
-use crate::network::{NetworkState, NetworkMode};
+use crate::network::{ping_stats, NetworkState, NetworkMode, PING_HISTORY_LEN};
 
 pub struct DebugPlugin;
 
@@ -10,16 +11,28 @@ impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(FrameTimeDiagnosticsPlugin)
             .add_systems(OnEnter(GameState::InGame), setup_debug_ui)
-            .add_systems(Update, (toggle_debug_ui, update_debug_info).run_if(in_state(GameState::InGame)));
+            .add_systems(OnExit(GameState::InGame), cleanup_debug_ui)
+            .add_systems(Update, (toggle_debug_ui, update_debug_info, update_ping_graph).run_if(in_state(GameState::InGame)));
     }
 }
 
 #[derive(Component)]
 struct DebugText;
 
+#[derive(Component)]
+struct PingGraph;
+
+#[derive(Component)]
+struct PingGraphBar(usize);
+
+type DebugOverlay = Or<(With<DebugText>, With<PingGraph>)>;
+
 #[derive(Resource)]
 struct DebugVisible(bool);
 
+const PING_GRAPH_HEIGHT: f32 = 60.0;
+const PING_GRAPH_SCALE_MS: f32 = 200.0;
+
 fn setup_debug_ui(mut commands: Commands) {
     commands.insert_resource(DebugVisible(false));
 
@@ -39,17 +52,55 @@ fn setup_debug_ui(mut commands: Commands) {
         },
         Visibility::Hidden,
     ));
+
+    commands
+        .spawn((
+            PingGraph,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                width: Val::Px(PING_HISTORY_LEN as f32 * 2.0),
+                height: Val::Px(PING_GRAPH_HEIGHT),
+                align_items: AlignItems::FlexEnd,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            for index in 0..PING_HISTORY_LEN {
+                parent.spawn((
+                    PingGraphBar(index),
+                    Node {
+                        width: Val::Px(2.0),
+                        height: Val::Px(0.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.4, 0.9, 0.4)),
+                ));
+            }
+        });
+}
+
+fn cleanup_debug_ui(
+    mut commands: Commands,
+    query: Query<Entity, DebugOverlay>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
 }
 
 fn toggle_debug_ui(
     keyboard: Res<ButtonInput<KeyCode>>,
     mut debug_visible: ResMut<DebugVisible>,
-    mut query: Query<&mut Visibility, With<DebugText>>,
+    mut query: Query<&mut Visibility, DebugOverlay>,
 ) {
     if keyboard.just_pressed(KeyCode::F3) {
         debug_visible.0 = !debug_visible.0;
         
-        if let Ok(mut visibility) = query.get_single_mut() {
+        for mut visibility in query.iter_mut() {
             *visibility = if debug_visible.0 {
                 Visibility::Visible
             } else {
@@ -59,22 +110,42 @@ fn toggle_debug_ui(
     }
 }
 
+fn update_ping_graph(
+    debug_visible: Res<DebugVisible>,
+    net_state: Res<NetworkState>,
+    mut bar_query: Query<(&PingGraphBar, &mut Node, &mut BackgroundColor)>,
+) {
+    if !debug_visible.0 {
+        return;
+    }
+
+    let offset = PING_HISTORY_LEN - net_state.ping_history.len();
+
+    for (bar, mut node, mut color) in bar_query.iter_mut() {
+        let sample = bar.0.checked_sub(offset).and_then(|index| net_state.ping_history.get(index));
+        let ratio = sample.map_or(0.0, |ping| (ping / PING_GRAPH_SCALE_MS).clamp(0.0, 1.0));
+
+        node.height = Val::Px(ratio * PING_GRAPH_HEIGHT);
+        *color = BackgroundColor(Color::srgb(0.4 + ratio * 0.6, 0.9 - ratio * 0.6, 0.4));
+    }
+}
+
 fn update_debug_info(
     diagnostics: Res<DiagnosticsStore>,
-    debug_visible: Res<DebugVisible>,
+    draw_target: Res<DrawTarget>,
     net_state: Res<NetworkState>,
     player_query: Query<(&Transform, &PlayerSpeed, &PlayerMovement), With<Player>>,
     camera_query: Query<&Transform, (With<Camera3d>, Without<Player>)>,
-    mut text_query: Query<&mut Text, With<DebugText>>,
+    mut text_query: Query<(&mut Text, &Visibility), With<DebugText>>,
     time: Res<Time>,
 ) {
-    if !debug_visible.0 {
+    let Ok((mut text, visibility)) = text_query.get_single_mut() else {
         return;
-    }
+    };
 
-    let Ok(mut text) = text_query.get_single_mut() else {
+    if *visibility == Visibility::Hidden {
         return;
-    };
+    }
 
     let fps = diagnostics
         .get(&FrameTimeDiagnosticsPlugin::FPS)
@@ -94,6 +165,12 @@ fn update_debug_info(
     
     if net_state.mode == NetworkMode::Client {
         debug_info.push_str(&format!("Ping: {:.0}ms\n", net_state.ping_ms));
+        if let Some(stats) = ping_stats(&net_state.ping_history) {
+            debug_info.push_str(&format!(
+                "  min {:.0} / avg {:.0} / max {:.0} / jitter {:.1}ms\n",
+                stats.min, stats.avg, stats.max, stats.jitter
+            ));
+        }
     } else if net_state.mode == NetworkMode::Server {
         debug_info.push_str("Mode: Server\n");
     } else {
@@ -127,6 +204,13 @@ fn update_debug_info(
         ));
     }
 
+    if let Some(hit) = draw_target.0 {
+        debug_info.push_str(&format!(
+            "Target: {:?} #{:08x}\n  UV: {:.2}, {:.2}\n\n",
+            hit.key.kind, hit.key.id(), hit.uv.x, hit.uv.y
+        ));
+    }
+
     debug_info.push_str(&format!("Time: {:.2}s", time.elapsed_secs()));
 
     **text = debug_info;
@@ -0,0 +1,394 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use crate::menu::GameState;
+use crate::network::{mark_id, DrawMark, NetworkEvent, NetworkState, PlayerRegistry};
+use crate::pause::Paused;
+use crate::physics::GameSystemSet;
+use crate::player::Player;
+use crate::world::{SurfaceIndex, SurfaceKind};
+
+pub struct DrawingPlugin;
+
+impl Plugin for DrawingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DrawTarget>()
+            .init_resource::<SurfaceMarks>()
+            .init_resource::<Stroke>()
+            .add_systems(Update, (update_draw_target, paint_marks).chain().after(GameSystemSet::Camera).run_if(in_state(GameState::InGame)))
+            .add_systems(Update, apply_received_marks)
+            .add_systems(OnExit(GameState::InGame), (clear_draw_target, clear_surface_marks));
+    }
+}
+
+pub const DRAW_REACH: f32 = 8.0;
+pub const MAX_MARKS_PER_SURFACE: usize = 2048;
+pub const MARK_SIZE: f32 = 0.02;
+pub const MARK_SHADE: u8 = 0;
+pub const MARK_SPACING: f32 = 0.03;
+
+#[derive(Resource, Default)]
+pub struct SurfaceMarks(pub HashMap<u32, VecDeque<DrawMark>>);
+
+impl SurfaceMarks {
+    pub fn apply(&mut self, mark: DrawMark) {
+        let marks = self.0.entry(mark.surface_id).or_default();
+        if marks.len() >= MAX_MARKS_PER_SURFACE {
+            marks.pop_front();
+        }
+        marks.push_back(mark);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Stroke {
+    pub last_point: Option<Vec3>,
+    pub sequence: u32,
+}
+
+impl Stroke {
+    pub fn next_mark(&mut self, player_id: u32, hit: &DrawHit) -> Option<DrawMark> {
+        if self.last_point.is_some_and(|last| last.distance(hit.point) < MARK_SPACING) {
+            return None;
+        }
+
+        self.last_point = Some(hit.point);
+        self.sequence = self.sequence.wrapping_add(1);
+        Some(DrawMark {
+            mark_id: mark_id(player_id, self.sequence),
+            surface_id: hit.key.id(),
+            uv: hit.uv,
+            shade: MARK_SHADE,
+            size: MARK_SIZE,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+impl CubeFace {
+    pub fn from_normal(normal: Vec3) -> Self {
+        let abs = normal.abs();
+        if abs.y >= abs.x && abs.y >= abs.z {
+            if normal.y >= 0.0 { CubeFace::PosY } else { CubeFace::NegY }
+        } else if abs.x >= abs.z {
+            if normal.x >= 0.0 { CubeFace::PosX } else { CubeFace::NegX }
+        } else if normal.z >= 0.0 {
+            CubeFace::PosZ
+        } else {
+            CubeFace::NegZ
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SurfaceKey {
+    pub kind: SurfaceKind,
+    pub index: u32,
+    pub face: CubeFace,
+}
+
+impl SurfaceKey {
+    pub fn id(&self) -> u32 {
+        ((self.kind as u32) << 28) | ((self.face as u32) << 24) | (self.index & 0x00FF_FFFF)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DrawHit {
+    pub entity: Entity,
+    pub key: SurfaceKey,
+    pub point: Vec3,
+    pub normal: Vec3,
+    pub uv: Vec2,
+}
+
+#[derive(Resource, Default)]
+pub struct DrawTarget(pub Option<DrawHit>);
+
+pub fn face_uv(local_point: Vec3, local_normal: Vec3, half_extents: Vec3) -> Vec2 {
+    let abs = local_normal.abs();
+    let (u, v, half_u, half_v) = if abs.y >= abs.x && abs.y >= abs.z {
+        (local_point.x, local_point.z, half_extents.x, half_extents.z)
+    } else if abs.x >= abs.z {
+        (local_point.z, local_point.y, half_extents.z, half_extents.y)
+    } else {
+        (local_point.x, local_point.y, half_extents.x, half_extents.y)
+    };
+
+    let across = |value: f32, half: f32| (value / half.max(f32::EPSILON) * 0.5 + 0.5).clamp(0.0, 1.0);
+    Vec2::new(across(u, half_u), across(v, half_v))
+}
+
+pub fn surface_key(surface: Option<(&SurfaceKind, &SurfaceIndex)>, normal: Vec3) -> Option<SurfaceKey> {
+    let (kind, index) = surface?;
+    Some(SurfaceKey {
+        kind: *kind,
+        index: index.0,
+        face: CubeFace::from_normal(normal),
+    })
+}
+
+type DrawSurface = (&'static SurfaceKind, &'static SurfaceIndex, &'static GlobalTransform, &'static Collider);
+
+pub fn cast_draw_ray(
+    rapier_context: &RapierContext,
+    origin: Vec3,
+    direction: Vec3,
+    filter: QueryFilter,
+    surface_query: &Query<DrawSurface>,
+) -> Option<DrawHit> {
+    let (entity, hit) = rapier_context.cast_ray_and_get_normal(origin, direction, DRAW_REACH, true, filter)?;
+    let (kind, index, transform, collider) = surface_query.get(entity).ok()?;
+    let half_extents = collider.as_cuboid()?.half_extents();
+
+    let local_from_world = transform.affine().inverse();
+    let local_point = local_from_world.transform_point3(hit.point);
+    let local_normal = local_from_world.transform_vector3(hit.normal);
+    let key = surface_key(Some((kind, index)), local_normal)?;
+
+    Some(DrawHit {
+        entity,
+        key,
+        point: hit.point,
+        normal: hit.normal,
+        uv: face_uv(local_point, local_normal, half_extents),
+    })
+}
+
+fn update_draw_target(
+    camera_query: Query<&GlobalTransform, With<Camera3d>>,
+    player_query: Query<Entity, With<Player>>,
+    surface_query: Query<DrawSurface>,
+    rapier_context: ReadRapierContext,
+    mut draw_target: ResMut<DrawTarget>,
+) {
+    let Ok(camera_transform) = camera_query.get_single() else {
+        return;
+    };
+
+    let rapier_context = rapier_context.single();
+    let mut filter = QueryFilter::default();
+    if let Ok(player_entity) = player_query.get_single() {
+        filter = filter.exclude_rigid_body(player_entity);
+    }
+
+    draw_target.0 = cast_draw_ray(
+        &rapier_context,
+        camera_transform.translation(),
+        camera_transform.forward().into(),
+        filter,
+        &surface_query,
+    );
+}
+
+fn paint_marks(
+    mouse: Res<ButtonInput<MouseButton>>,
+    paused: Res<Paused>,
+    draw_target: Res<DrawTarget>,
+    camera_query: Query<&GlobalTransform, With<Camera3d>>,
+    mut stroke: ResMut<Stroke>,
+    mut surface_marks: ResMut<SurfaceMarks>,
+    (mut net_state, player_registry): (ResMut<NetworkState>, Res<PlayerRegistry>),
+) {
+    if paused.0 || !mouse.pressed(MouseButton::Left) {
+        stroke.last_point = None;
+        return;
+    }
+
+    let (Some(hit), Ok(camera_transform)) = (draw_target.0, camera_query.get_single()) else {
+        return;
+    };
+
+    let Some(mark) = stroke.next_mark(net_state.local_player_id, &hit) else {
+        return;
+    };
+
+    surface_marks.apply(mark);
+    net_state.share_draw_mark(&player_registry, mark, camera_transform.translation(), hit.point);
+}
+
+fn clear_draw_target(mut draw_target: ResMut<DrawTarget>) {
+    draw_target.0 = None;
+}
+
+fn apply_received_marks(
+    mut events: EventReader<NetworkEvent>,
+    mut surface_marks: ResMut<SurfaceMarks>,
+) {
+    for event in events.read() {
+        if let NetworkEvent::DrawMarkReceived(mark) = event {
+            surface_marks.apply(*mark);
+        }
+    }
+}
+
+fn clear_surface_marks(mut surface_marks: ResMut<SurfaceMarks>) {
+    surface_marks.0.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::network::{handle_network_events, NetworkMode, ServerList};
+    use crate::world::{floor_tile_index, GameConfig, FLOOR_GRID_SIZE, FLOOR_TILE_SIZE, PLATFORM_SIZE};
+    use std::net::{Ipv4Addr, UdpSocket};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn mark(mark_id: u64, surface_id: u32) -> DrawMark {
+        DrawMark { mark_id, surface_id, uv: Vec2::splat(0.5), shade: 1, size: 0.1 }
+    }
+
+    const FACES: [CubeFace; 6] = [CubeFace::PosX, CubeFace::NegX, CubeFace::PosY, CubeFace::NegY, CubeFace::PosZ, CubeFace::NegZ];
+
+    #[test]
+    fn untagged_colliders_have_no_surface_key() {
+        assert_eq!(surface_key(None, Vec3::Y), None);
+        assert_eq!(
+            surface_key(Some((&SurfaceKind::Checkerboard, &SurfaceIndex(0))), Vec3::Y),
+            Some(SurfaceKey { kind: SurfaceKind::Checkerboard, index: 0, face: CubeFace::PosY }),
+        );
+    }
+
+    #[test]
+    fn distinct_faces_never_share_an_id() {
+        let grid = FLOOR_GRID_SIZE;
+        let tiles = (-grid..grid).flat_map(|x| (-grid..grid).map(move |z| (SurfaceKind::Checkerboard, floor_tile_index(x, z))));
+        let surfaces: Vec<(SurfaceKind, u32)> = tiles.chain([(SurfaceKind::Platform, 0)]).collect();
+
+        let mut ids = HashMap::new();
+        for (kind, index) in surfaces {
+            for face in FACES {
+                let key = SurfaceKey { kind, index, face };
+                assert_eq!(ids.insert(key.id(), key), None, "{key:?} collides");
+            }
+        }
+    }
+
+    #[test]
+    fn the_same_face_keeps_its_id() {
+        let hit = |normal: Vec3| surface_key(Some((&SurfaceKind::Platform, &SurfaceIndex(0))), normal).unwrap().id();
+        assert_eq!(hit(Vec3::Y), hit(Vec3::new(0.1, 0.99, -0.05).normalize()));
+        assert_ne!(hit(Vec3::Y), hit(Vec3::X));
+        assert_eq!(floor_tile_index(3, -2), floor_tile_index(3, -2));
+    }
+
+    #[test]
+    fn received_marks_are_grouped_by_surface() {
+        let mut surface_marks = SurfaceMarks::default();
+        surface_marks.apply(mark(1, 7));
+        surface_marks.apply(mark(2, 9));
+        surface_marks.apply(mark(3, 7));
+
+        let ids: Vec<u64> = surface_marks.0[&7].iter().map(|mark| mark.mark_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(surface_marks.0[&9].len(), 1);
+    }
+
+    #[test]
+    fn surfaces_keep_only_the_newest_marks() {
+        let mut surface_marks = SurfaceMarks::default();
+        for mark_id in 0..(MAX_MARKS_PER_SURFACE as u64 + 5) {
+            surface_marks.apply(mark(mark_id, 1));
+        }
+
+        let marks = &surface_marks.0[&1];
+        assert_eq!(marks.len(), MAX_MARKS_PER_SURFACE);
+        assert_eq!(marks.front().unwrap().mark_id, 5);
+    }
+
+    #[test]
+    fn face_corners_span_the_whole_texture() {
+        let platform = PLATFORM_SIZE / 2.0;
+        let top = |point: Vec3| face_uv(point, Vec3::Y, platform);
+        assert_eq!(top(Vec3::new(-2.0, 0.5, -2.0)), Vec2::ZERO);
+        assert!(top(Vec3::new(2.0, 0.5, 2.0)).abs_diff_eq(Vec2::ONE, 1e-5));
+
+        let side = |point: Vec3| face_uv(point, Vec3::NEG_X, platform);
+        assert_eq!(side(Vec3::new(-2.0, -0.5, -2.0)), Vec2::ZERO);
+        assert!(side(Vec3::new(-2.0, 0.5, 2.0)).abs_diff_eq(Vec2::ONE, 1e-5));
+
+        let tile = Vec3::new(FLOOR_TILE_SIZE / 2.0, 0.1, FLOOR_TILE_SIZE / 2.0);
+        assert!(face_uv(Vec3::new(0.0, 0.1, 0.0), Vec3::Y, tile).abs_diff_eq(Vec2::splat(0.5), 1e-5));
+    }
+
+    fn platform_hit(point: Vec3) -> DrawHit {
+        DrawHit {
+            entity: Entity::PLACEHOLDER,
+            key: SurfaceKey { kind: SurfaceKind::Platform, index: 0, face: CubeFace::PosY },
+            point,
+            normal: Vec3::Y,
+            uv: Vec2::new(0.6, 0.4),
+        }
+    }
+
+    #[test]
+    fn strokes_space_out_marks_and_number_them_per_player() {
+        let mut stroke = Stroke::default();
+        let first = stroke.next_mark(3, &platform_hit(Vec3::ZERO)).unwrap();
+        assert_eq!(stroke.next_mark(3, &platform_hit(Vec3::X * MARK_SPACING / 2.0)), None);
+        let second = stroke.next_mark(3, &platform_hit(Vec3::X * MARK_SPACING * 2.0)).unwrap();
+
+        assert_eq!(first.mark_id, mark_id(3, 1));
+        assert_eq!(second.mark_id, mark_id(3, 2));
+        assert_eq!(first.surface_id, platform_hit(Vec3::ZERO).key.id());
+    }
+
+    #[test]
+    fn painted_marks_reach_the_server_unchanged() {
+        let server_socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        server_socket.set_read_timeout(Some(Duration::from_millis(250))).unwrap();
+        let client_socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        client_socket.connect(server_socket.local_addr().unwrap()).unwrap();
+        let client_addr = client_socket.local_addr().unwrap();
+
+        let hit = platform_hit(Vec3::new(0.5, 2.0, 0.5));
+        let mut mouse = ButtonInput::<MouseButton>::default();
+        mouse.press(MouseButton::Left);
+        let mut client_state = NetworkState::default();
+        client_state.mode = NetworkMode::Client;
+        client_state.socket = Some(Arc::new(client_socket));
+        client_state.local_player_id = 1;
+
+        let mut client = World::new();
+        client.insert_resource(mouse);
+        client.insert_resource(Paused(false));
+        client.insert_resource(DrawTarget(Some(hit)));
+        client.insert_resource(client_state);
+        client.init_resource::<Stroke>();
+        client.init_resource::<SurfaceMarks>();
+        client.init_resource::<PlayerRegistry>();
+        client.spawn((Camera3d::default(), GlobalTransform::from_translation(Vec3::new(0.0, 3.0, 2.0))));
+        client.run_system_once(paint_marks).unwrap();
+
+        let mut server_state = NetworkState::default();
+        server_state.mode = NetworkMode::Server;
+        server_state.socket = Some(Arc::new(server_socket));
+        let mut registry = PlayerRegistry::default();
+        registry.client_addresses.insert(1, client_addr);
+
+        let mut server = World::new();
+        server.insert_resource(server_state);
+        server.insert_resource(registry);
+        server.init_resource::<ServerList>();
+        server.init_resource::<GameConfig>();
+        server.init_resource::<SurfaceMarks>();
+        server.init_resource::<Events<NetworkEvent>>();
+        server.run_system_once(handle_network_events).unwrap();
+        server.run_system_once(apply_received_marks).unwrap();
+
+        let local = &client.resource::<SurfaceMarks>().0;
+        assert_eq!(local[&hit.key.id()].len(), 1);
+        assert_eq!(&server.resource::<SurfaceMarks>().0, local);
+    }
+}
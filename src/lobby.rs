@@ -1,20 +1,24 @@
 use bevy::prelude::*;
 use bevy::window::CursorGrabMode;
 use crate::menu::GameState;
-use crate::network::{NetworkState, ServerList, NetworkEvent};
+use std::time::{Duration, Instant};
+use crate::network::{NetworkConfig, NetworkState, NetworkMode, ServerList, NetworkEvent};
+use crate::settings::{ChangedButton, ThemedButton, UiTheme, BUTTON_BORDER_WIDTH};
 
 pub struct LobbyPlugin;
 
 impl Plugin for LobbyPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<ConnectionStatus>()
             .add_systems(OnEnter(GameState::Lobby), setup_lobby)
             .add_systems(Update, (
                 lobby_button_system,
                 lobby_action,
                 update_server_list_ui,
                 handle_connection_events,
-            ).run_if(in_state(GameState::Lobby)))
+                update_connection_status,
+            ).chain().run_if(in_state(GameState::Lobby)))
             .add_systems(OnExit(GameState::Lobby), cleanup_lobby);
     }
 }
@@ -33,13 +37,48 @@ enum LobbyButton {
 #[derive(Component)]
 struct ServerListContainer;
 
+#[derive(Component)]
+struct LobbyStatusText;
+
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub enum ConnectionStatus {
+    #[default]
+    Idle,
+    Connecting { since: Instant },
+    Failed(String),
+}
+
+impl ConnectionStatus {
+    pub fn is_connecting(&self) -> bool {
+        matches!(self, ConnectionStatus::Connecting { .. })
+    }
+
+    pub fn check_timeout(&mut self, now: Instant, timeout: Duration) -> bool {
+        let timed_out = matches!(self, ConnectionStatus::Connecting { since } if now.duration_since(*since) >= timeout);
+        if timed_out {
+            *self = ConnectionStatus::Failed("Connection timed out".to_string());
+        }
+        timed_out
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            ConnectionStatus::Idle => String::new(),
+            ConnectionStatus::Connecting { .. } => "Connecting...".to_string(),
+            ConnectionStatus::Failed(reason) => reason.clone(),
+        }
+    }
+}
+
 const NORMAL_BUTTON: Color = Color::srgba(0.15, 0.15, 0.15, 0.9);
-const HOVERED_BUTTON: Color = Color::srgba(0.25, 0.25, 0.25, 0.95);
-const PRESSED_BUTTON: Color = Color::srgba(0.35, 0.75, 0.35, 0.95);
 
 fn setup_lobby(
     mut commands: Commands,
+    network_config: Res<NetworkConfig>,
     mut net_state: ResMut<NetworkState>,
+    mut connection_status: ResMut<ConnectionStatus>,
     mut windows: Query<&mut Window>,
 ) {
     for mut window in windows.iter_mut() {
@@ -47,9 +86,10 @@ fn setup_lobby(
         window.cursor_options.visible = true;
     }
 
-    if let Ok(state) = NetworkState::start_discovery() {
-        *net_state = state;
-    }
+    *connection_status = match refresh_discovery(&mut net_state, &network_config) {
+        Ok(()) => ConnectionStatus::Idle,
+        Err(error) => ConnectionStatus::Failed(format!("Discovery failed: {}", error)),
+    };
 
     commands.spawn((
         Camera2d,
@@ -83,6 +123,16 @@ fn setup_lobby(
                 },
             ));
 
+            parent.spawn((
+                Text::new(connection_status.message()),
+                TextFont {
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.9, 0.35, 0.35)),
+                LobbyStatusText,
+            ));
+
             parent
                 .spawn((
                     Node {
@@ -127,11 +177,13 @@ fn spawn_button(parent: &mut ChildBuilder, text: &str, button_type: LobbyButton)
             Node {
                 width: Val::Px(200.0),
                 height: Val::Px(60.0),
+                border: UiRect::all(Val::Px(BUTTON_BORDER_WIDTH)),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 ..default()
             },
             BackgroundColor(NORMAL_BUTTON),
+            BorderColor(Color::NONE),
             button_type,
         ))
         .with_children(|parent| {
@@ -147,57 +199,99 @@ fn spawn_button(parent: &mut ChildBuilder, text: &str, button_type: LobbyButton)
 }
 
 fn lobby_button_system(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<Button>),
-    >,
+    theme: Res<UiTheme>,
+    mut interaction_query: Query<ThemedButton, ChangedButton>,
 ) {
-    for (interaction, mut color) in &mut interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
-                *color = PRESSED_BUTTON.into();
-            }
-            Interaction::Hovered => {
-                *color = HOVERED_BUTTON.into();
-            }
-            Interaction::None => {
-                *color = NORMAL_BUTTON.into();
-            }
-        }
+    for (interaction, mut color, mut border) in &mut interaction_query {
+        theme.style_button(*interaction, &mut color, &mut border);
     }
 }
 
 fn lobby_action(
-    interaction_query: Query<(&Interaction, &LobbyButton), (Changed<Interaction>, With<Button>)>,
+    interaction_query: Query<(&Interaction, &LobbyButton), ChangedButton>,
     mut next_state: ResMut<NextState<GameState>>,
+    network_config: Res<NetworkConfig>,
     mut net_state: ResMut<NetworkState>,
+    mut server_list: ResMut<ServerList>,
+    mut connection_status: ResMut<ConnectionStatus>,
 ) {
     for (interaction, button) in &interaction_query {
         if *interaction == Interaction::Pressed {
-            match button {
+            if connection_status.is_connecting() {
+                continue;
+            }
+
+            let result = match button {
                 LobbyButton::CreateServer => {
-                    if let Ok(state) = NetworkState::create_server() {
-                        *net_state = state;
-                        next_state.set(GameState::InGame);
-                    }
+                    NetworkState::create_server(&network_config)
+                        .map(|state| {
+                            *net_state = state;
+                            next_state.set(GameState::InGame);
+                        })
+                        .map_err(|error| format!("Could not create server: {}", error))
                 }
                 LobbyButton::Refresh => {
-                    if let Ok(state) = NetworkState::start_discovery() {
-                        *net_state = state;
-                    }
+                    server_list.remove_stale(Duration::from_secs(3));
+                    refresh_discovery(&mut net_state, &network_config)
+                        .map_err(|error| format!("Discovery failed: {}", error))
                 }
                 LobbyButton::Back => {
                     next_state.set(GameState::Menu);
+                    Ok(())
                 }
                 LobbyButton::JoinServer(addr) => {
-                    if net_state.connect_to_server(*addr).is_ok() {
-                    }
+                    net_state.connect_to_server(*addr, &network_config)
+                        .map(|_| {
+                            *connection_status = ConnectionStatus::Connecting { since: Instant::now() };
+                        })
+                        .map_err(|error| format!("Could not join {}: {}", addr, error))
                 }
+            };
+
+            match result {
+                Ok(()) if connection_status.is_connecting() => {}
+                Ok(()) => *connection_status = ConnectionStatus::Idle,
+                Err(reason) => *connection_status = ConnectionStatus::Failed(reason),
             }
         }
     }
 }
 
+fn update_connection_status(
+    network_config: Res<NetworkConfig>,
+    mut connection_status: ResMut<ConnectionStatus>,
+    mut net_state: ResMut<NetworkState>,
+    mut text_query: Query<(&mut Text, &mut TextColor), With<LobbyStatusText>>,
+) {
+    if connection_status.check_timeout(Instant::now(), CONNECT_TIMEOUT) {
+        *net_state = NetworkState::default();
+        let _ = refresh_discovery(&mut net_state, &network_config);
+    }
+
+    if !connection_status.is_changed() {
+        return;
+    }
+
+    let color = match *connection_status {
+        ConnectionStatus::Failed(_) => Color::srgb(0.9, 0.35, 0.35),
+        _ => Color::srgba(0.7, 0.7, 0.7, 1.0),
+    };
+
+    for (mut text, mut text_color) in text_query.iter_mut() {
+        **text = connection_status.message();
+        *text_color = TextColor(color);
+    }
+}
+
+fn refresh_discovery(net_state: &mut NetworkState, config: &NetworkConfig) -> Result<(), std::io::Error> {
+    if net_state.mode == NetworkMode::None && net_state.socket.is_some() {
+        net_state.rebroadcast_discovery(config)
+    } else {
+        *net_state = NetworkState::start_discovery(config)?;
+        Ok(())
+    }
+}
+
 fn update_server_list_ui(
     mut commands: Commands,
     server_list: Res<ServerList>,
@@ -235,11 +329,13 @@ fn update_server_list_ui(
                                 height: Val::Px(70.0),
                                 margin: UiRect::all(Val::Px(5.0)),
                                 padding: UiRect::all(Val::Px(15.0)),
+                                border: UiRect::all(Val::Px(BUTTON_BORDER_WIDTH)),
                                 flex_direction: FlexDirection::Column,
                                 justify_content: JustifyContent::Center,
                                 ..default()
                             },
                             BackgroundColor(NORMAL_BUTTON),
+                            BorderColor(Color::NONE),
                             LobbyButton::JoinServer(*addr),
                         ))
                         .with_children(|parent| {
@@ -269,10 +365,12 @@ fn update_server_list_ui(
 fn handle_connection_events(
     mut events: EventReader<NetworkEvent>,
     mut next_state: ResMut<NextState<GameState>>,
+    mut connection_status: ResMut<ConnectionStatus>,
 ) {
     for event in events.read() {
         match event {
             NetworkEvent::ConnectedToServer(_) => {
+                *connection_status = ConnectionStatus::Idle;
                 next_state.set(GameState::InGame);
             }
             _ => {}
@@ -288,3 +386,52 @@ fn cleanup_lobby(
         commands.entity(entity).despawn_recursive();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::{bind_first_available, NetworkMessage, SERVER_PORTS};
+    use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+    use std::sync::Arc;
+
+    #[test]
+    fn refresh_rebroadcasts_on_the_same_socket() {
+        let loopback = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let config = NetworkConfig { bind_address: loopback, broadcast_address: loopback };
+        let (server, _) = bind_first_available(loopback, SERVER_PORTS).unwrap();
+        server.set_read_timeout(Some(Duration::from_secs(1))).unwrap();
+        let socket = Arc::new(UdpSocket::bind((loopback, 0)).unwrap());
+        let mut net_state = NetworkState::default();
+        net_state.socket = Some(socket.clone());
+
+        let mut buf = [0u8; 1024];
+        for _ in 0..2 {
+            refresh_discovery(&mut net_state, &config).unwrap();
+
+            let (size, from) = server.recv_from(&mut buf).unwrap();
+            assert_eq!(from, socket.local_addr().unwrap());
+            assert!(matches!(bincode::deserialize(&buf[..size]), Ok(NetworkMessage::DiscoveryRequest)));
+        }
+
+        assert!(Arc::ptr_eq(net_state.socket.as_ref().unwrap(), &socket));
+        assert!(net_state.mode == NetworkMode::None);
+    }
+
+    #[test]
+    fn connecting_times_out_into_failed() {
+        let since = Instant::now();
+        let mut status = ConnectionStatus::Connecting { since };
+        assert_eq!(status.message(), "Connecting...");
+
+        assert!(!status.check_timeout(since + CONNECT_TIMEOUT / 2, CONNECT_TIMEOUT));
+        assert!(status.is_connecting());
+
+        assert!(status.check_timeout(since + CONNECT_TIMEOUT, CONNECT_TIMEOUT));
+        assert_eq!(status, ConnectionStatus::Failed("Connection timed out".to_string()));
+        assert!(!status.check_timeout(since + CONNECT_TIMEOUT * 2, CONNECT_TIMEOUT));
+
+        let mut idle = ConnectionStatus::Idle;
+        assert!(!idle.check_timeout(since + CONNECT_TIMEOUT * 2, CONNECT_TIMEOUT));
+        assert_eq!(idle.message(), "");
+    }
+}
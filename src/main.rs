@@ -1,12 +1,17 @@
 mod audio;
 mod camera;
 mod debug;
+mod drawing;
 mod lobby;
 mod menu;
 mod network;
+mod pause;
 mod physics;
 mod player;
 mod remote_player;
+mod scoreboard;
+mod screenshot;
+mod settings;
 mod skybox;
 mod world;
 
@@ -15,14 +20,20 @@ use bevy::window::PresentMode;
 use audio::AudioPlugin;
 use camera::CameraPlugin;
 use debug::DebugPlugin;
+use drawing::DrawingPlugin;
 use lobby::LobbyPlugin;
 use menu::MenuPlugin;
 use network::NetworkPlugin;
+use pause::PausePlugin;
 use physics::PhysicsPlugin;
 use player::PlayerPlugin;
 use remote_player::RemotePlayerPlugin;
+use scoreboard::ScoreboardPlugin;
+use screenshot::ScreenshotPlugin;
+use settings::SettingsPlugin;
 use skybox::SkyboxPlugin;
 use world::WorldPlugin;
+use network::NetworkConfig;
 use std::env;
 
 fn main() {
@@ -44,9 +55,13 @@ fn main() {
         ..default()
     }))
     .add_plugins(bevy::diagnostic::LogDiagnosticsPlugin::default())
+    .insert_resource(NetworkConfig::from_args(&args))
     .add_plugins(MenuPlugin)
+    .add_plugins(SettingsPlugin)
     .add_plugins(LobbyPlugin)
     .add_plugins(NetworkPlugin)
-    .add_plugins((WorldPlugin, PlayerPlugin, RemotePlayerPlugin, PhysicsPlugin, CameraPlugin, DebugPlugin, SkyboxPlugin, AudioPlugin))
+    .add_plugins(ScoreboardPlugin)
+    .add_plugins(PausePlugin)
+    .add_plugins((WorldPlugin, PlayerPlugin, RemotePlayerPlugin, PhysicsPlugin, CameraPlugin, DebugPlugin, SkyboxPlugin, AudioPlugin, DrawingPlugin, ScreenshotPlugin))
     .run();
 }
@@ -1,6 +1,10 @@
 use bevy::prelude::*;
 use bevy::app::AppExit;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::ui::UiSystem;
 use bevy::window::CursorGrabMode;
+use crate::world::{parse_seed, GameConfig};
+use crate::settings::{ChangedButton, ThemedButton, UiTheme, BUTTON_BORDER_WIDTH};
 
 pub struct MenuPlugin;
 
@@ -8,12 +12,20 @@ impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app
             .init_state::<GameState>()
+            .init_resource::<SeedInput>()
+            .init_resource::<MenuFocus>()
             .add_systems(OnEnter(GameState::Menu), setup_menu)
             .add_systems(Update, (
                 button_system,
+                seed_text_input,
                 menu_action,
+                update_seed_text,
                 rotate_menu_camera,
-            ).run_if(in_state(GameState::Menu)))
+            ).chain().run_if(in_state(GameState::Menu)))
+            .add_systems(PreUpdate, keyboard_menu_navigation
+                .after(UiSystem::Focus)
+                .run_if(not(in_state(GameState::InGame))))
+            .add_systems(PostUpdate, highlight_focused_button.run_if(not(in_state(GameState::InGame))))
             .add_systems(OnExit(GameState::Menu), cleanup_menu);
     }
 }
@@ -23,6 +35,7 @@ pub enum GameState {
     #[default]
     Menu,
     Lobby,
+    Settings,
     InGame,
 }
 
@@ -35,14 +48,53 @@ struct MenuCamera;
 #[derive(Component)]
 enum MenuButton {
     Multiplayer,
+    Settings,
+    EditSeed,
+    RandomizeSeed,
     Quit,
 }
 
+#[derive(Component)]
+struct SeedText;
+
+#[derive(Resource, Default)]
+struct SeedInput {
+    editing: bool,
+    buffer: String,
+}
+
+#[derive(Resource, Default)]
+pub struct MenuFocus {
+    pub index: Option<usize>,
+    focused: Option<Entity>,
+    activated: Option<Entity>,
+}
+
+pub fn step_focus(current: Option<usize>, len: usize, step: isize) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    match current {
+        Some(index) => Some((index as isize + step).rem_euclid(len as isize) as usize),
+        None if step < 0 => Some(len - 1),
+        None => Some(0),
+    }
+}
+
+const MAX_SEED_DIGITS: usize = 19;
+
 const NORMAL_BUTTON: Color = Color::srgba(0.15, 0.15, 0.15, 0.9);
-const HOVERED_BUTTON: Color = Color::srgba(0.25, 0.25, 0.25, 0.95);
-const PRESSED_BUTTON: Color = Color::srgba(0.35, 0.75, 0.35, 0.95);
 
-fn setup_menu(mut commands: Commands, mut windows: Query<&mut Window>) {
+fn setup_menu(
+    mut commands: Commands,
+    mut windows: Query<&mut Window>,
+    mut seed_input: ResMut<SeedInput>,
+    game_config: Res<GameConfig>,
+) {
+    seed_input.editing = false;
+    seed_input.buffer.clear();
+
     for mut window in windows.iter_mut() {
         window.cursor_options.grab_mode = CursorGrabMode::None;
         window.cursor_options.visible = true;
@@ -100,6 +152,45 @@ fn setup_menu(mut commands: Commands, mut windows: Query<&mut Window>) {
             ));
 
             spawn_button(parent, "Multiplayer", MenuButton::Multiplayer);
+            spawn_button(parent, "Settings", MenuButton::Settings);
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(10.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            Button,
+                            Node {
+                                width: Val::Px(250.0),
+                                height: Val::Px(65.0),
+                                margin: UiRect::all(Val::Px(10.0)),
+                                border: UiRect::all(Val::Px(BUTTON_BORDER_WIDTH)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            BackgroundColor(NORMAL_BUTTON),
+                            BorderColor(Color::NONE),
+                            MenuButton::EditSeed,
+                        ))
+                        .with_children(|parent| {
+                            parent.spawn((
+                                Text::new(format!("Seed: {}", game_config.seed)),
+                                TextFont {
+                                    font_size: 26.0,
+                                    ..default()
+                                },
+                                TextColor(Color::WHITE),
+                                SeedText,
+                            ));
+                        });
+
+                    spawn_button(parent, "Randomize Seed", MenuButton::RandomizeSeed);
+                });
+
             spawn_button(parent, "Quit", MenuButton::Quit);
         });
 }
@@ -112,11 +203,13 @@ fn spawn_button(parent: &mut ChildBuilder, text: &str, button_type: MenuButton)
                 width: Val::Px(250.0),
                 height: Val::Px(65.0),
                 margin: UiRect::all(Val::Px(10.0)),
+                border: UiRect::all(Val::Px(BUTTON_BORDER_WIDTH)),
                 justify_content: JustifyContent::Center,
                 align_items: AlignItems::Center,
                 ..default()
             },
             BackgroundColor(NORMAL_BUTTON),
+            BorderColor(Color::NONE),
             button_type,
         ))
         .with_children(|parent| {
@@ -132,30 +225,20 @@ fn spawn_button(parent: &mut ChildBuilder, text: &str, button_type: MenuButton)
 }
 
 fn button_system(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor),
-        (Changed<Interaction>, With<Button>),
-    >,
+    theme: Res<UiTheme>,
+    mut interaction_query: Query<ThemedButton, ChangedButton>,
 ) {
-    for (interaction, mut color) in &mut interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
-                *color = PRESSED_BUTTON.into();
-            }
-            Interaction::Hovered => {
-                *color = HOVERED_BUTTON.into();
-            }
-            Interaction::None => {
-                *color = NORMAL_BUTTON.into();
-            }
-        }
+    for (interaction, mut color, mut border) in &mut interaction_query {
+        theme.style_button(*interaction, &mut color, &mut border);
     }
 }
 
 fn menu_action(
-    interaction_query: Query<(&Interaction, &MenuButton), (Changed<Interaction>, With<Button>)>,
+    interaction_query: Query<(&Interaction, &MenuButton), ChangedButton>,
     mut next_state: ResMut<NextState<GameState>>,
     mut exit: EventWriter<AppExit>,
+    mut seed_input: ResMut<SeedInput>,
+    mut game_config: ResMut<GameConfig>,
 ) {
     for (interaction, menu_button) in &interaction_query {
         if *interaction == Interaction::Pressed {
@@ -163,6 +246,17 @@ fn menu_action(
                 MenuButton::Multiplayer => {
                     next_state.set(GameState::Lobby);
                 }
+                MenuButton::Settings => {
+                    next_state.set(GameState::Settings);
+                }
+                MenuButton::EditSeed => {
+                    seed_input.editing = true;
+                    seed_input.buffer.clear();
+                }
+                MenuButton::RandomizeSeed => {
+                    seed_input.editing = false;
+                    game_config.randomize_seed();
+                }
                 MenuButton::Quit => {
                     exit.send(AppExit::Success);
                 }
@@ -171,6 +265,142 @@ fn menu_action(
     }
 }
 
+fn keyboard_menu_navigation(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    seed_input: Res<SeedInput>,
+    mut focus: ResMut<MenuFocus>,
+    mut button_query: Query<(Entity, &GlobalTransform, &mut Interaction), With<Button>>,
+) {
+    let released = focus.activated.take().and_then(|entity| button_query.get_mut(entity).ok());
+    if let Some((_, _, mut interaction)) = released.filter(|(_, _, interaction)| **interaction == Interaction::Pressed) {
+        *interaction = Interaction::None;
+    }
+
+    let newly_hovered = button_query
+        .iter_mut()
+        .find(|(_, _, interaction)| interaction.is_changed() && **interaction == Interaction::Hovered)
+        .map(|(entity, _, _)| entity);
+
+    let mut buttons: Vec<(Entity, Vec3)> = button_query
+        .iter()
+        .map(|(entity, transform, _)| (entity, transform.translation()))
+        .collect();
+    buttons.sort_by(|a, b| a.1.y.total_cmp(&b.1.y).then(a.1.x.total_cmp(&b.1.x)));
+
+    if focus.focused.is_some_and(|focused| buttons.iter().all(|(entity, _)| *entity != focused)) {
+        focus.index = None;
+    }
+
+    if let Some(hovered) = newly_hovered.and_then(|hovered| buttons.iter().position(|(entity, _)| *entity == hovered)) {
+        focus.index = Some(hovered);
+    }
+
+    if seed_input.editing {
+        focus.focused = focus.index.and_then(|index| buttons.get(index)).map(|(entity, _)| *entity);
+        return;
+    }
+
+    let gamepad_pressed = |button: GamepadButton| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+
+    let step = if keyboard.any_just_pressed([KeyCode::ArrowUp, KeyCode::KeyW]) || gamepad_pressed(GamepadButton::DPadUp) {
+        -1
+    } else if keyboard.any_just_pressed([KeyCode::ArrowDown, KeyCode::KeyS, KeyCode::Tab]) || gamepad_pressed(GamepadButton::DPadDown) {
+        1
+    } else {
+        0
+    };
+
+    if step != 0 {
+        focus.index = step_focus(focus.index, buttons.len(), step);
+    }
+
+    focus.focused = focus.index.and_then(|index| buttons.get(index)).map(|(entity, _)| *entity);
+
+    let activate = keyboard.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter])
+        || gamepad_pressed(GamepadButton::South);
+
+    let target = focus.focused.filter(|_| activate);
+    if let Some((entity, _, mut interaction)) = target.and_then(|entity| button_query.get_mut(entity).ok()) {
+        *interaction = Interaction::Pressed;
+        focus.activated = Some(entity);
+    }
+}
+
+fn highlight_focused_button(
+    theme: Res<UiTheme>,
+    focus: Res<MenuFocus>,
+    mut button_query: Query<(Entity, ThemedButton), With<Button>>,
+) {
+    for (entity, (interaction, mut color, mut border)) in button_query.iter_mut() {
+        let shown = if focus.focused == Some(entity) && *interaction == Interaction::None {
+            Interaction::Hovered
+        } else {
+            *interaction
+        };
+
+        theme.style_button(shown, &mut color, &mut border);
+    }
+}
+
+fn seed_text_input(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut seed_input: ResMut<SeedInput>,
+    mut game_config: ResMut<GameConfig>,
+) {
+    if !seed_input.editing {
+        keyboard_events.clear();
+        return;
+    }
+
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Character(chars) => {
+                for c in chars.chars().filter(|c| c.is_ascii_digit()) {
+                    if seed_input.buffer.len() < MAX_SEED_DIGITS {
+                        seed_input.buffer.push(c);
+                    }
+                }
+            }
+            Key::Backspace => {
+                seed_input.buffer.pop();
+            }
+            Key::Enter => {
+                if let Some(seed) = parse_seed(&seed_input.buffer) {
+                    game_config.seed = seed;
+                }
+                seed_input.editing = false;
+            }
+            Key::Escape => {
+                seed_input.editing = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn update_seed_text(
+    seed_input: Res<SeedInput>,
+    game_config: Res<GameConfig>,
+    mut text_query: Query<&mut Text, With<SeedText>>,
+) {
+    if !seed_input.is_changed() && !game_config.is_changed() {
+        return;
+    }
+
+    for mut text in text_query.iter_mut() {
+        **text = if seed_input.editing {
+            format!("Seed: {}_", seed_input.buffer)
+        } else {
+            format!("Seed: {}", game_config.seed)
+        };
+    }
+}
+
 fn rotate_menu_camera(
     time: Res<Time>,
     mut camera_query: Query<&mut Transform, With<MenuCamera>>,
@@ -201,3 +431,54 @@ fn cleanup_menu(
         commands.entity(entity).despawn_recursive();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn focus_wraps_around_the_menu() {
+        assert_eq!(step_focus(None, 3, 1), Some(0));
+        assert_eq!(step_focus(None, 3, -1), Some(2));
+        assert_eq!(step_focus(Some(2), 3, 1), Some(0));
+        assert_eq!(step_focus(Some(0), 3, -1), Some(2));
+        assert_eq!(step_focus(Some(1), 3, 1), Some(2));
+        assert_eq!(step_focus(Some(0), 0, 1), None);
+    }
+
+    #[test]
+    fn enter_activates_the_focused_button_under_a_resting_mouse() {
+        let mut world = World::new();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<MenuFocus>();
+        world.init_resource::<SeedInput>();
+        world.init_resource::<NextState<GameState>>();
+        world.init_resource::<Events<AppExit>>();
+        world.init_resource::<GameConfig>();
+
+        let mut spawn_button = |menu_button: MenuButton, y: f32, interaction: Interaction| {
+            world.spawn((Button, interaction, GlobalTransform::from_xyz(0.0, y, 0.0), menu_button)).id()
+        };
+        spawn_button(MenuButton::Multiplayer, 0.0, Interaction::Hovered);
+        let settings = spawn_button(MenuButton::Settings, 50.0, Interaction::None);
+        spawn_button(MenuButton::Quit, 100.0, Interaction::None);
+
+        let navigate = world.register_system(keyboard_menu_navigation);
+        world.run_system(navigate).unwrap();
+        assert_eq!(world.resource::<MenuFocus>().index, Some(0));
+
+        for key in [KeyCode::ArrowDown, KeyCode::Enter] {
+            let mut keyboard = world.resource_mut::<ButtonInput<KeyCode>>();
+            keyboard.reset_all();
+            keyboard.press(key);
+            world.run_system(navigate).unwrap();
+        }
+
+        assert_eq!(world.resource::<MenuFocus>().index, Some(1));
+        assert_eq!(world.get::<Interaction>(settings), Some(&Interaction::Pressed));
+
+        world.run_system_once(menu_action).unwrap();
+        assert!(matches!(*world.resource::<NextState<GameState>>(), NextState::Pending(GameState::Settings)));
+    }
+}
@@ -1,15 +1,20 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::net::{UdpSocket, SocketAddr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, UdpSocket, SocketAddr};
+use std::ops::Range;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::{Duration, Instant};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::player::PlayerDimensions;
+use crate::world::{seeded_spawn_position, GameConfig};
 
 pub struct NetworkPlugin;
 
 impl Plugin for NetworkPlugin {
     fn build(&self, app: &mut App) {
         app
+            .init_resource::<NetworkConfig>()
             .init_resource::<NetworkState>()
             .init_resource::<ServerList>()
             .init_resource::<PlayerRegistry>()
@@ -23,15 +28,81 @@ impl Plugin for NetworkPlugin {
     }
 }
 
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct NetworkConfig {
+    pub bind_address: IpAddr,
+    pub broadcast_address: IpAddr,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            bind_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            broadcast_address: IpAddr::V4(Ipv4Addr::BROADCAST),
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn from_args(args: &[String]) -> Self {
+        let mut config = Self::default();
+        let value_of = |flag: &str| {
+            args.iter()
+                .position(|arg| arg == flag)
+                .and_then(|index| args.get(index + 1))
+                .and_then(|value| value.parse::<IpAddr>().ok())
+        };
+
+        if let Some(bind_address) = value_of("--bind") {
+            config.bind_address = bind_address;
+        }
+        if let Some(broadcast_address) = value_of("--broadcast") {
+            config.broadcast_address = broadcast_address;
+        }
+
+        config
+    }
+
+    pub fn listen_address(&self) -> IpAddr {
+        match self.bind_address {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        }
+    }
+
+    pub fn discovery_targets(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        SERVER_PORTS
+            .filter(|port| *port != DISCOVERY_PORT)
+            .map(|port| SocketAddr::new(self.broadcast_address, port))
+    }
+
+    pub fn announce_target(&self) -> SocketAddr {
+        SocketAddr::new(self.broadcast_address, DISCOVERY_PORT)
+    }
+}
+
 #[derive(Resource)]
 pub struct NetworkState {
     pub mode: NetworkMode,
     pub socket: Option<Arc<UdpSocket>>,
     pub server_addr: Option<SocketAddr>,
     pub local_player_id: u32,
+    pub server_port: u16,
+    pub server_host: Option<IpAddr>,
+    pub assigned_spawn: Option<Vec3>,
     pub last_discovery: Instant,
     pub ping_ms: f32,
+    pub ping_history: VecDeque<f32>,
     pub last_ping_sent: Instant,
+    pub update_rate: f32,
+    pub last_update_sent: Instant,
+    pub last_sent_transform: Option<(Vec3, Quat)>,
+    pub use_delta_updates: bool,
+    pub delta_base: Option<(u16, Vec3)>,
+    pub updates_since_keyframe: u32,
+    pub fragments: FragmentBuffer,
+    pub seen_marks: SeenMarks,
+    next_fragment_id: AtomicU32,
 }
 
 impl Default for NetworkState {
@@ -41,11 +112,213 @@ impl Default for NetworkState {
             socket: None,
             server_addr: None,
             local_player_id: 0,
+            server_port: 0,
+            server_host: None,
+            assigned_spawn: None,
             last_discovery: Instant::now(),
             ping_ms: 0.0,
+            ping_history: VecDeque::with_capacity(PING_HISTORY_LEN),
             last_ping_sent: Instant::now(),
+            update_rate: 20.0,
+            last_update_sent: Instant::now(),
+            last_sent_transform: None,
+            use_delta_updates: true,
+            delta_base: None,
+            updates_since_keyframe: 0,
+            fragments: FragmentBuffer::default(),
+            seen_marks: SeenMarks::default(),
+            next_fragment_id: AtomicU32::new(0),
+        }
+    }
+}
+
+pub const SERVER_PORTS: Range<u16> = 7878..7888;
+pub const DISCOVERY_PORT: u16 = 7879;
+pub const UPDATE_POSITION_EPSILON: f32 = 0.001;
+pub const UPDATE_ROTATION_EPSILON: f32 = 0.0001;
+pub const UPDATE_KEEPALIVE: Duration = Duration::from_secs(1);
+
+pub fn should_send_update(
+    since_last: Duration,
+    update_rate: f32,
+    last_sent: Option<(Vec3, Quat)>,
+    position: Vec3,
+    rotation: Quat,
+) -> bool {
+    if update_rate > 0.0 && since_last.as_secs_f32() < 1.0 / update_rate {
+        return false;
+    }
+
+    if since_last >= UPDATE_KEEPALIVE {
+        return true;
+    }
+
+    match last_sent {
+        Some((last_position, last_rotation)) => {
+            last_position.distance(position) > UPDATE_POSITION_EPSILON
+                || !last_rotation.abs_diff_eq(rotation, UPDATE_ROTATION_EPSILON)
+        }
+        None => true,
+    }
+}
+
+pub const DELTA_UNIT: f32 = 0.001;
+pub const KEYFRAME_INTERVAL: u32 = 20;
+
+pub fn quantize_delta(delta: Vec3) -> Option<[i16; 3]> {
+    let quantize = |value: f32| {
+        let steps = (value / DELTA_UNIT).round();
+        (steps.is_finite() && steps >= i16::MIN as f32 && steps <= i16::MAX as f32).then_some(steps as i16)
+    };
+    Some([quantize(delta.x)?, quantize(delta.y)?, quantize(delta.z)?])
+}
+
+pub fn dequantize_delta(delta: [i16; 3]) -> Vec3 {
+    Vec3::new(delta[0] as f32, delta[1] as f32, delta[2] as f32) * DELTA_UNIT
+}
+
+pub fn encode_player_update(
+    player_id: u32,
+    base: Option<(u16, Vec3)>,
+    updates_since_keyframe: u32,
+    position: Vec3,
+    rotation: Quat,
+) -> (NetworkMessage, (u16, Vec3)) {
+    let delta = base
+        .filter(|_| updates_since_keyframe < KEYFRAME_INTERVAL)
+        .and_then(|(keyframe, base)| quantize_delta(position - base).map(|delta| (keyframe, base, delta)));
+
+    match delta {
+        Some((keyframe, base, delta)) => (
+            NetworkMessage::PlayerDelta { player_id, keyframe, delta, rotation },
+            (keyframe, base),
+        ),
+        None => {
+            let keyframe = base.map_or(0, |(keyframe, _)| keyframe.wrapping_add(1));
+            (
+                NetworkMessage::PlayerKeyframe { player_id, keyframe, position, rotation },
+                (keyframe, position),
+            )
+        }
+    }
+}
+
+pub const PING_HISTORY_LEN: usize = 120;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PingStats {
+    pub min: f32,
+    pub avg: f32,
+    pub max: f32,
+    pub jitter: f32,
+}
+
+pub fn ping_stats<'a>(samples: impl IntoIterator<Item = &'a f32>) -> Option<PingStats> {
+    let samples: Vec<f32> = samples.into_iter().copied().collect();
+    if samples.is_empty() {
+        return None;
+    }
+
+    let min = samples.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = samples.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let avg = samples.iter().sum::<f32>() / samples.len() as f32;
+    let jitter = if samples.len() > 1 {
+        samples.windows(2).map(|pair| (pair[1] - pair[0]).abs()).sum::<f32>() / (samples.len() - 1) as f32
+    } else {
+        0.0
+    };
+
+    Some(PingStats { min, avg, max, jitter })
+}
+
+pub const MAX_PACKET_SIZE: usize = 65507;
+pub const FRAGMENT_PAYLOAD_SIZE: usize = 32 * 1024;
+pub const FRAGMENT_TIMEOUT: Duration = Duration::from_secs(2);
+pub const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+pub const MAX_FRAGMENTS: u16 = MAX_MESSAGE_SIZE.div_ceil(FRAGMENT_PAYLOAD_SIZE) as u16;
+pub const MAX_PENDING_MESSAGES: usize = 64;
+
+struct PartialMessage {
+    parts: Vec<Option<Vec<u8>>>,
+    received: usize,
+    started: Instant,
+}
+
+#[derive(Default)]
+pub struct FragmentBuffer {
+    pending: HashMap<(SocketAddr, u32), PartialMessage>,
+}
+
+impl FragmentBuffer {
+    pub fn insert(&mut self, from: SocketAddr, id: u32, index: u16, total: u16, bytes: Vec<u8>) -> Option<Vec<u8>> {
+        if total == 0 || total > MAX_FRAGMENTS || index >= total || bytes.len() > FRAGMENT_PAYLOAD_SIZE {
+            return None;
+        }
+
+        if !self.pending.contains_key(&(from, id)) && self.pending.len() >= MAX_PENDING_MESSAGES {
+            return None;
+        }
+
+        let partial = self.pending.entry((from, id)).or_insert_with(|| PartialMessage {
+            parts: vec![None; total as usize],
+            received: 0,
+            started: Instant::now(),
+        });
+
+        if partial.parts.len() != total as usize {
+            return None;
+        }
+
+        let slot = &mut partial.parts[index as usize];
+        if slot.is_none() {
+            *slot = Some(bytes);
+            partial.received += 1;
+        }
+
+        if partial.received < partial.parts.len() {
+            return None;
+        }
+
+        let partial = self.pending.remove(&(from, id))?;
+        Some(partial.parts.into_iter().flatten().flatten().collect())
+    }
+
+    pub fn prune(&mut self, timeout: Duration) {
+        self.pending.retain(|_, partial| partial.started.elapsed() < timeout);
+    }
+}
+
+pub fn bind_first_available(bind_address: IpAddr, ports: Range<u16>) -> Result<(UdpSocket, u16), std::io::Error> {
+    let mut last_error = std::io::Error::new(std::io::ErrorKind::AddrNotAvailable, "no ports to bind");
+
+    for port in ports.filter(|port| *port != DISCOVERY_PORT) {
+        match UdpSocket::bind((bind_address, port)) {
+            Ok(socket) => return Ok((socket, port)),
+            Err(error) => last_error = error,
         }
     }
+
+    Err(last_error)
+}
+
+pub fn join_target(source: SocketAddr, host: Option<IpAddr>, port: u16) -> SocketAddr {
+    SocketAddr::new(host.unwrap_or(source.ip()), port)
+}
+
+pub fn fragment_message(id: u32, data: &[u8], payload_size: usize) -> Vec<NetworkMessage> {
+    let chunks: Vec<&[u8]> = data.chunks(payload_size.max(1)).collect();
+    let total = chunks.len() as u16;
+
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, bytes)| NetworkMessage::Fragment {
+            id,
+            index: index as u16,
+            total,
+            bytes: bytes.to_vec(),
+        })
+        .collect()
 }
 
 #[derive(Default, PartialEq, Clone, Copy)]
@@ -61,6 +334,12 @@ pub struct ServerList {
     pub servers: HashMap<SocketAddr, ServerInfo>,
 }
 
+impl ServerList {
+    pub fn remove_stale(&mut self, max_age: Duration) {
+        self.servers.retain(|_, info| info.last_seen.elapsed() < max_age);
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ServerInfo {
     pub name: String,
@@ -73,6 +352,147 @@ pub struct ServerInfo {
 pub struct PlayerRegistry {
     pub players: HashMap<u32, PlayerData>,
     pub client_addresses: HashMap<u32, SocketAddr>,
+    pub history: HashMap<u32, PositionHistory>,
+    pub delta_bases: HashMap<u32, (u16, Vec3)>,
+}
+
+impl PlayerRegistry {
+    pub fn clear(&mut self) {
+        self.players.clear();
+        self.client_addresses.clear();
+        self.history.clear();
+        self.delta_bases.clear();
+    }
+
+    pub fn remove_player(&mut self, id: u32) {
+        self.players.remove(&id);
+        self.history.remove(&id);
+        self.delta_bases.remove(&id);
+    }
+
+    pub fn resolve_delta(&self, id: u32, keyframe: u16, delta: [i16; 3]) -> Option<Vec3> {
+        let (base_keyframe, base) = self.delta_bases.get(&id)?;
+        (*base_keyframe == keyframe).then(|| *base + dequantize_delta(delta))
+    }
+
+    pub fn apply_update(&mut self, id: u32, position: Vec3, rotation: Quat, timestamp: u128) -> bool {
+        if !is_valid_transform(position, rotation) {
+            return false;
+        }
+
+        let player = self.players.entry(id).or_insert(PlayerData {
+            id,
+            position,
+            rotation,
+            entity: None,
+        });
+        player.position = position;
+        player.rotation = rotation;
+        self.record_position(id, timestamp, position);
+        true
+    }
+
+    pub fn record_position(&mut self, id: u32, timestamp: u128, position: Vec3) {
+        self.history.entry(id).or_default().record(timestamp, position);
+    }
+
+    pub fn position_at(&self, id: u32, timestamp: u128) -> Option<Vec3> {
+        self.history.get(&id).and_then(|history| history.position_at(timestamp))
+    }
+
+    pub fn player_for_address(&self, addr: SocketAddr) -> Option<u32> {
+        self.client_addresses.iter().find(|(_, client_addr)| **client_addr == addr).map(|(id, _)| *id)
+    }
+
+    pub fn blocking_player(&self, actor: u32, origin: Vec3, target: Vec3, timestamp: u128, dimensions: &PlayerDimensions) -> Option<u32> {
+        if !origin.is_finite() || !target.is_finite() {
+            return Some(actor);
+        }
+
+        self.history
+            .iter()
+            .filter(|(id, _)| **id != actor)
+            .filter_map(|(id, history)| Some((*id, history.position_at(timestamp)?)))
+            .find(|(_, position)| {
+                let axis = Vec3::Y * dimensions.half_height;
+                segment_distance(origin, target, *position - axis, *position + axis) < dimensions.radius
+            })
+            .map(|(id, _)| id)
+    }
+}
+
+pub const POSITION_HISTORY_MS: u128 = 1000;
+pub const INTERPOLATION_DELAY_MS: u128 = 100;
+pub const MAX_REWIND_MS: u128 = 500;
+
+pub fn rewind_time(now: u128, rewind_ms: u32) -> u128 {
+    now.saturating_sub((rewind_ms as u128).min(MAX_REWIND_MS))
+}
+
+pub fn segment_distance(a_start: Vec3, a_end: Vec3, b_start: Vec3, b_end: Vec3) -> f32 {
+    let a = a_end - a_start;
+    let b = b_end - b_start;
+    let offset = a_start - b_start;
+    let (a_len, b_len) = (a.length_squared(), b.length_squared());
+    let (ab, a_offset, b_offset) = (a.dot(b), a.dot(offset), b.dot(offset));
+
+    let denom = a_len * b_len - ab * ab;
+    let mut s = if a_len > 1e-6 && denom > 1e-6 { ((ab * b_offset - b_len * a_offset) / denom).clamp(0.0, 1.0) } else { 0.0 };
+    let mut t = if b_len > 1e-6 { (ab * s + b_offset) / b_len } else { 0.0 };
+
+    if !(0.0..=1.0).contains(&t) {
+        t = t.clamp(0.0, 1.0);
+        s = if a_len > 1e-6 { ((ab * t - a_offset) / a_len).clamp(0.0, 1.0) } else { 0.0 };
+    }
+
+    (a_start + a * s).distance(b_start + b * t)
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PositionHistory {
+    snapshots: VecDeque<(u128, Vec3)>,
+}
+
+impl PositionHistory {
+    pub fn record(&mut self, timestamp: u128, position: Vec3) {
+        if self.snapshots.back().is_some_and(|(last, _)| timestamp < *last) {
+            return;
+        }
+
+        self.snapshots.push_back((timestamp, position));
+
+        while self.snapshots.front().is_some_and(|(oldest, _)| oldest + POSITION_HISTORY_MS < timestamp) {
+            self.snapshots.pop_front();
+        }
+    }
+
+    pub fn position_at(&self, timestamp: u128) -> Option<Vec3> {
+        let (first_time, first_position) = *self.snapshots.front()?;
+        if timestamp <= first_time {
+            return Some(first_position);
+        }
+
+        for ((from_time, from), (to_time, to)) in self.snapshots.iter().zip(self.snapshots.iter().skip(1)) {
+            if timestamp <= *to_time {
+                let span = (to_time - from_time).max(1) as f32;
+                let t = (timestamp - from_time) as f32 / span;
+                return Some(from.lerp(*to, t));
+            }
+        }
+
+        self.snapshots.back().map(|(_, position)| *position)
+    }
+}
+
+pub fn is_valid_transform(position: Vec3, rotation: Quat) -> bool {
+    position.is_finite() && rotation.is_finite() && rotation.length_squared() > 1e-6
+}
+
+pub fn now_millis() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
 }
 
 #[derive(Clone, Debug)]
@@ -89,12 +509,64 @@ pub enum NetworkEvent {
     PlayerJoined(u32),
     PlayerLeft(u32),
     PlayerMoved(u32, Vec3, Quat),
+    DrawMarkReceived(DrawMark),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DrawMark {
+    pub mark_id: u64,
+    pub surface_id: u32,
+    pub uv: Vec2,
+    pub shade: u8,
+    pub size: f32,
+}
+
+pub const MAX_MARK_SIZE: f32 = 0.25;
+
+pub fn mark_id(player_id: u32, sequence: u32) -> u64 {
+    ((player_id as u64) << 32) | sequence as u64
+}
+
+pub fn mark_owner(mark_id: u64) -> u32 {
+    (mark_id >> 32) as u32
+}
+pub const SEEN_MARK_CAPACITY: usize = 4096;
+
+#[derive(Default)]
+pub struct SeenMarks {
+    ids: HashSet<u64>,
+    order: VecDeque<u64>,
+}
+
+impl SeenMarks {
+    pub fn insert(&mut self, mark_id: u64) -> bool {
+        if !self.ids.insert(mark_id) {
+            return false;
+        }
+
+        self.order.push_back(mark_id);
+        let excess = self.order.len().saturating_sub(SEEN_MARK_CAPACITY);
+        for oldest in self.order.drain(..excess) {
+            self.ids.remove(&oldest);
+        }
+        true
+    }
+}
+
+impl DrawMark {
+    pub fn clamped(self) -> Self {
+        let uv = if self.uv.is_finite() { self.uv.clamp(Vec2::ZERO, Vec2::ONE) } else { Vec2::ZERO };
+        let size = if self.size.is_finite() { self.size.clamp(0.0, MAX_MARK_SIZE) } else { 0.0 };
+        Self { uv, size, ..self }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum NetworkMessage {
     ServerAnnounce {
         name: String,
+        host: Option<IpAddr>,
+        port: u16,
         player_count: u8,
         max_players: u8,
     },
@@ -104,6 +576,8 @@ pub enum NetworkMessage {
     },
     JoinAccept {
         player_id: u32,
+        world_seed: u64,
+        spawn_position: Vec3,
         existing_players: Vec<(u32, Vec3, Quat)>,
     },
     PlayerSpawn {
@@ -116,6 +590,18 @@ pub enum NetworkMessage {
         position: Vec3,
         rotation: Quat,
     },
+    PlayerKeyframe {
+        player_id: u32,
+        keyframe: u16,
+        position: Vec3,
+        rotation: Quat,
+    },
+    PlayerDelta {
+        player_id: u32,
+        keyframe: u16,
+        delta: [i16; 3],
+        rotation: Quat,
+    },
     PlayerDisconnect {
         player_id: u32,
     },
@@ -125,49 +611,66 @@ pub enum NetworkMessage {
     Pong {
         timestamp: u128,
     },
+    Fragment {
+        id: u32,
+        index: u16,
+        total: u16,
+        bytes: Vec<u8>,
+    },
+    DrawMark {
+        mark: DrawMark,
+        origin: Vec3,
+        point: Vec3,
+        rewind_ms: u32,
+    },
 }
 
 impl NetworkState {
-    pub fn create_server() -> Result<Self, std::io::Error> {
-        let socket = UdpSocket::bind("0.0.0.0:7878")?;
+    pub fn create_server(config: &NetworkConfig) -> Result<Self, std::io::Error> {
+        let (socket, port) = bind_first_available(config.listen_address(), SERVER_PORTS)?;
         socket.set_nonblocking(true)?;
         socket.set_broadcast(true)?;
         
+        let server_host = (!config.bind_address.is_unspecified()).then_some(config.bind_address);
+
         let state = NetworkState {
             mode: NetworkMode::Server,
             socket: Some(Arc::new(socket)),
-            server_addr: None,
-            local_player_id: 0,
-            last_discovery: Instant::now(),
-            ping_ms: 0.0,
-            last_ping_sent: Instant::now(),
+            server_port: port,
+            server_host,
+            ..default()
         };
         
         Ok(state)
     }
     
-    pub fn start_discovery() -> Result<Self, std::io::Error> {
-        let socket = UdpSocket::bind("0.0.0.0:7879")?;
+    pub fn start_discovery(config: &NetworkConfig) -> Result<Self, std::io::Error> {
+        let socket = UdpSocket::bind((config.listen_address(), DISCOVERY_PORT))?;
         socket.set_nonblocking(true)?;
         socket.set_broadcast(true)?;
         
-        let msg = NetworkMessage::DiscoveryRequest;
-        let data = bincode::serialize(&msg).unwrap();
-        socket.send_to(&data, "255.255.255.255:7878")?;
-        
-        Ok(NetworkState {
+        let state = NetworkState {
             mode: NetworkMode::None,
             socket: Some(Arc::new(socket)),
-            server_addr: None,
-            local_player_id: 0,
-            last_discovery: Instant::now(),
-            ping_ms: 0.0,
-            last_ping_sent: Instant::now(),
-        })
+            ..default()
+        };
+        state.rebroadcast_discovery(config)?;
+        
+        Ok(state)
     }
     
-    pub fn connect_to_server(&mut self, server_addr: SocketAddr) -> Result<(), std::io::Error> {
-        let socket = UdpSocket::bind("0.0.0.0:0")?;
+    pub fn rebroadcast_discovery(&self, config: &NetworkConfig) -> Result<(), std::io::Error> {
+        if let Some(socket) = &self.socket {
+            let data = bincode::serialize(&NetworkMessage::DiscoveryRequest).unwrap();
+            for target in config.discovery_targets() {
+                socket.send_to(&data, target)?;
+            }
+        }
+        Ok(())
+    }
+    
+    pub fn connect_to_server(&mut self, server_addr: SocketAddr, config: &NetworkConfig) -> Result<(), std::io::Error> {
+        let socket = UdpSocket::bind((config.bind_address, 0))?;
         socket.set_nonblocking(true)?;
         socket.connect(server_addr)?;
         
@@ -184,28 +687,99 @@ impl NetworkState {
         Ok(())
     }
     
-    pub fn send_message(&self, msg: &NetworkMessage) -> Result<(), std::io::Error> {
+    pub fn disconnect(&mut self, registry: &PlayerRegistry) {
         if let Some(socket) = &self.socket {
-            let data = bincode::serialize(msg).unwrap();
+            let msg = NetworkMessage::PlayerDisconnect {
+                player_id: self.local_player_id,
+            };
+            let data = bincode::serialize(&msg).unwrap();
+
             match self.mode {
                 NetworkMode::Server => {
-                    socket.send_to(&data, "255.255.255.255:7879")?;
+                    for client_addr in registry.client_addresses.values() {
+                        let _ = socket.send_to(&data, client_addr);
+                    }
                 }
                 NetworkMode::Client => {
-                    socket.send(&data)?;
+                    let _ = socket.send(&data);
                 }
                 NetworkMode::None => {}
             }
         }
+
+        *self = NetworkState::default();
+    }
+    
+    pub fn encode_packets(&self, msg: &NetworkMessage) -> Vec<Vec<u8>> {
+        let data = bincode::serialize(msg).unwrap();
+        if data.len() <= MAX_PACKET_SIZE {
+            return vec![data];
+        }
+
+        let id = self.next_fragment_id.fetch_add(1, Ordering::Relaxed);
+        fragment_message(id, &data, FRAGMENT_PAYLOAD_SIZE)
+            .iter()
+            .map(|fragment| bincode::serialize(fragment).unwrap())
+            .collect()
+    }
+    
+    pub fn send_to_all<'a>(&self, msg: &NetworkMessage, addrs: impl IntoIterator<Item = &'a SocketAddr>) {
+        if let Some(socket) = &self.socket {
+            let packets = self.encode_packets(msg);
+            for addr in addrs {
+                for packet in &packets {
+                    let _ = socket.send_to(packet, addr);
+                }
+            }
+        }
+    }
+    
+    pub fn send_to(&self, msg: &NetworkMessage, addr: SocketAddr) -> Result<(), std::io::Error> {
+        if let Some(socket) = &self.socket {
+            for packet in self.encode_packets(msg) {
+                socket.send_to(&packet, addr)?;
+            }
+        }
+        Ok(())
+    }
+    
+    pub fn rewind_ms(&self) -> u32 {
+        (INTERPOLATION_DELAY_MS as f32 + self.ping_ms / 2.0) as u32
+    }
+
+    pub fn share_draw_mark(&mut self, registry: &PlayerRegistry, mark: DrawMark, origin: Vec3, point: Vec3) {
+        self.seen_marks.insert(mark.mark_id);
+        let msg = NetworkMessage::DrawMark { mark, origin, point, rewind_ms: self.rewind_ms() };
+
+        match self.mode {
+            NetworkMode::Server => self.send_to_all(&msg, registry.client_addresses.values()),
+            NetworkMode::Client => {
+                let _ = self.send_message(&msg);
+            }
+            NetworkMode::None => {}
+        }
+    }
+
+    pub fn send_message(&self, msg: &NetworkMessage) -> Result<(), std::io::Error> {
+        if self.mode != NetworkMode::Client {
+            return Ok(());
+        }
+
+        if let Some(socket) = &self.socket {
+            for packet in self.encode_packets(msg) {
+                socket.send(&packet)?;
+            }
+        }
         Ok(())
     }
 }
 
-fn handle_network_events(
+pub(crate) fn handle_network_events(
     mut net_state: ResMut<NetworkState>,
     mut server_list: ResMut<ServerList>,
     mut player_registry: ResMut<PlayerRegistry>,
     mut events: EventWriter<NetworkEvent>,
+    mut game_config: ResMut<GameConfig>,
 ) {
     let socket = match &net_state.socket {
         Some(s) => s.clone(),
@@ -216,15 +790,46 @@ fn handle_network_events(
     let mut pending_updates = Vec::new();
     
     while let Ok((size, addr)) = socket.recv_from(&mut buf) {
-        if let Ok(msg) = bincode::deserialize::<NetworkMessage>(&buf[..size]) {
-            pending_updates.push((msg, addr));
+        match bincode::deserialize::<NetworkMessage>(&buf[..size]) {
+            Ok(NetworkMessage::Fragment { id, index, total, bytes }) => {
+                let reassembled = net_state.fragments.insert(addr, id, index, total, bytes)
+                    .and_then(|data| bincode::deserialize::<NetworkMessage>(&data).ok());
+                if let Some(msg) = reassembled {
+                    pending_updates.push((msg, addr));
+                }
+            }
+            Ok(msg) => pending_updates.push((msg, addr)),
+            Err(_) => {}
         }
     }
     
+    net_state.fragments.prune(FRAGMENT_TIMEOUT);
+    
     for (msg, addr) in pending_updates {
+        let msg = match msg {
+            NetworkMessage::PlayerKeyframe { player_id, keyframe, position, rotation } => {
+                let from_owner = net_state.mode != NetworkMode::Server
+                    || player_registry.client_addresses.get(&player_id) == Some(&addr);
+                if !from_owner {
+                    continue;
+                }
+                if is_valid_transform(position, rotation) {
+                    player_registry.delta_bases.insert(player_id, (keyframe, position));
+                }
+                NetworkMessage::PlayerUpdate { player_id, position, rotation }
+            }
+            NetworkMessage::PlayerDelta { player_id, keyframe, delta, rotation } => {
+                let Some(position) = player_registry.resolve_delta(player_id, keyframe, delta) else {
+                    continue;
+                };
+                NetworkMessage::PlayerUpdate { player_id, position, rotation }
+            }
+            msg => msg,
+        };
+
         match msg {
-            NetworkMessage::ServerAnnounce { name, player_count, max_players } => {
-                server_list.servers.insert(addr, ServerInfo {
+            NetworkMessage::ServerAnnounce { name, host, port, player_count, max_players } => {
+                server_list.servers.insert(join_target(addr, host, port), ServerInfo {
                     name,
                     player_count,
                     max_players,
@@ -235,57 +840,64 @@ fn handle_network_events(
                 if net_state.mode == NetworkMode::Server {
                     let response = NetworkMessage::ServerAnnounce {
                         name: "LAN Server".to_string(),
+                        host: net_state.server_host,
+                        port: net_state.server_port,
                         player_count: player_registry.players.len() as u8,
                         max_players: 8,
                     };
-                    let _ = net_state.send_message(&response);
+                    let _ = net_state.send_to(&response, addr);
                 }
             }
             NetworkMessage::JoinRequest { .. } => {
                 if net_state.mode == NetworkMode::Server {
                     let new_id = player_registry.players.len() as u32 + 1;
+                    net_state.updates_since_keyframe = KEYFRAME_INTERVAL;
                     
                     let existing: Vec<_> = player_registry.players.values()
                         .map(|p| (p.id, p.position, p.rotation))
                         .collect();
                     
+                    let spawn_position = seeded_spawn_position(game_config.seed, new_id, PlayerDimensions::default().radius);
                     let accept = NetworkMessage::JoinAccept {
                         player_id: new_id,
+                        world_seed: game_config.seed,
+                        spawn_position,
                         existing_players: existing,
                     };
                     
-                    let data = bincode::serialize(&accept).unwrap();
-                    let _ = socket.send_to(&data, addr);
+                    let _ = net_state.send_to(&accept, addr);
                     
                     player_registry.client_addresses.insert(new_id, addr);
                     
                     player_registry.players.insert(new_id, PlayerData {
                         id: new_id,
-                        position: Vec3::ZERO,
+                        position: spawn_position,
                         rotation: Quat::IDENTITY,
                         entity: None,
                     });
                     
                     let spawn_msg = NetworkMessage::PlayerSpawn {
                         player_id: new_id,
-                        position: Vec3::ZERO,
+                        position: spawn_position,
                         rotation: Quat::IDENTITY,
                     };
-                    let spawn_data = bincode::serialize(&spawn_msg).unwrap();
-                    for (id, client_addr) in player_registry.client_addresses.iter() {
-                        if *id != new_id {
-                            let _ = socket.send_to(&spawn_data, client_addr);
-                        }
-                    }
+                    let others = player_registry.client_addresses.iter()
+                        .filter(|(id, _)| **id != new_id)
+                        .map(|(_, client_addr)| client_addr);
+                    net_state.send_to_all(&spawn_msg, others);
                     
                     events.send(NetworkEvent::PlayerJoined(new_id));
                 }
             }
-            NetworkMessage::JoinAccept { player_id, existing_players } => {
+            NetworkMessage::JoinAccept { player_id, world_seed, spawn_position, existing_players } => {
                 net_state.local_player_id = player_id;
+                net_state.assigned_spawn = spawn_position.is_finite().then_some(spawn_position);
+                if game_config.seed != world_seed {
+                    game_config.seed = world_seed;
+                }
                 
                 for (id, pos, rot) in existing_players {
-                    if id != player_id {
+                    if id != player_id && is_valid_transform(pos, rot) {
                         player_registry.players.insert(id, PlayerData {
                             id,
                             position: pos,
@@ -299,7 +911,7 @@ fn handle_network_events(
                 events.send(NetworkEvent::ConnectedToServer(addr));
             }
             NetworkMessage::PlayerSpawn { player_id, position, rotation } => {
-                if player_id != net_state.local_player_id {
+                if player_id != net_state.local_player_id && is_valid_transform(position, rotation) {
                     player_registry.players.insert(player_id, PlayerData {
                         id: player_id,
                         position,
@@ -310,43 +922,71 @@ fn handle_network_events(
                 }
             }
             NetworkMessage::PlayerUpdate { player_id, position, rotation } => {
-                if net_state.mode == NetworkMode::Server {
-                    if let Some(player) = player_registry.players.get_mut(&player_id) {
-                        player.position = position;
-                        player.rotation = rotation;
-                    }
-                    
+                let is_server = net_state.mode == NetworkMode::Server;
+                if is_server && player_registry.client_addresses.get(&player_id) != Some(&addr) {
+                    continue;
+                }
+                if !is_server && player_id == net_state.local_player_id {
+                    continue;
+                }
+
+                if !player_registry.apply_update(player_id, position, rotation, now_millis()) {
+                    warn!("Dropping invalid update for player {} from {}", player_id, addr);
+                    continue;
+                }
+
+                if is_server {
                     let update_msg = NetworkMessage::PlayerUpdate {
                         player_id,
                         position,
                         rotation,
                     };
-                    let data = bincode::serialize(&update_msg).unwrap();
-                    
-                    for (id, client_addr) in player_registry.client_addresses.iter() {
-                        if *id != player_id {
-                            let _ = socket.send_to(&data, client_addr);
-                        }
-                    }
-                } else if player_id != net_state.local_player_id {
-                    if let Some(player) = player_registry.players.get_mut(&player_id) {
-                        player.position = position;
-                        player.rotation = rotation;
-                    } else {
-                        player_registry.players.insert(player_id, PlayerData {
-                            id: player_id,
-                            position,
-                            rotation,
-                            entity: None,
-                        });
-                    }
+                    let others = player_registry.client_addresses.iter()
+                        .filter(|(id, _)| **id != player_id)
+                        .map(|(_, client_addr)| client_addr);
+                    net_state.send_to_all(&update_msg, others);
+                } else {
                     events.send(NetworkEvent::PlayerMoved(player_id, position, rotation));
                 }
             }
             NetworkMessage::PlayerDisconnect { player_id } => {
-                player_registry.players.remove(&player_id);
+                if net_state.mode == NetworkMode::Server {
+                    player_registry.client_addresses.remove(&player_id);
+                    
+                    net_state.send_to_all(&NetworkMessage::PlayerDisconnect { player_id }, player_registry.client_addresses.values());
+                }
+                
+                player_registry.remove_player(player_id);
                 events.send(NetworkEvent::PlayerLeft(player_id));
             }
+            NetworkMessage::DrawMark { mark, origin, point, rewind_ms } => {
+                let mark = mark.clamped();
+                let is_server = net_state.mode == NetworkMode::Server;
+                if is_server {
+                    let Some(actor) = player_registry.player_for_address(addr) else {
+                        continue;
+                    };
+                    if mark_owner(mark.mark_id) != actor {
+                        continue;
+                    }
+                    let render_time = rewind_time(now_millis(), rewind_ms);
+                    if player_registry.blocking_player(actor, origin, point, render_time, &PlayerDimensions::default()).is_some() {
+                        continue;
+                    }
+                }
+
+                if !net_state.seen_marks.insert(mark.mark_id) {
+                    continue;
+                }
+
+                if is_server {
+                    let relay = NetworkMessage::DrawMark { mark, origin, point, rewind_ms };
+                    let others = player_registry.client_addresses.values().filter(|client_addr| **client_addr != addr);
+                    net_state.send_to_all(&relay, others);
+                }
+
+                events.send(NetworkEvent::DrawMarkReceived(mark));
+            }
             NetworkMessage::Ping { timestamp } => {
                 if net_state.mode == NetworkMode::Server {
                     let pong = NetworkMessage::Pong { timestamp };
@@ -356,11 +996,13 @@ fn handle_network_events(
             }
             NetworkMessage::Pong { timestamp } => {
                 if net_state.mode == NetworkMode::Client {
-                    let now = std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap()
-                        .as_millis();
-                    net_state.ping_ms = (now - timestamp) as f32;
+                    let now = now_millis();
+                    net_state.ping_ms = now.saturating_sub(timestamp) as f32;
+                    let ping_ms = net_state.ping_ms;
+                    if net_state.ping_history.len() >= PING_HISTORY_LEN {
+                        net_state.ping_history.pop_front();
+                    }
+                    net_state.ping_history.push_back(ping_ms);
                 }
             }
             _ => {}
@@ -369,6 +1011,7 @@ fn handle_network_events(
 }
 
 fn update_server_discovery(
+    network_config: Res<NetworkConfig>,
     mut net_state: ResMut<NetworkState>,
     mut server_list: ResMut<ServerList>,
 ) {
@@ -376,50 +1019,68 @@ fn update_server_discovery(
         if net_state.last_discovery.elapsed() > Duration::from_secs(2) {
             let msg = NetworkMessage::ServerAnnounce {
                 name: "LAN Server".to_string(),
+                host: net_state.server_host,
+                port: net_state.server_port,
                 player_count: 0,
                 max_players: 8,
             };
-            let _ = net_state.send_message(&msg);
+            let _ = net_state.send_to(&msg, network_config.announce_target());
             net_state.last_discovery = Instant::now();
         }
     }
     
-    server_list.servers.retain(|_, info| {
-        info.last_seen.elapsed() < Duration::from_secs(5)
-    });
+    server_list.remove_stale(Duration::from_secs(5));
 }
 
 fn sync_players(
-    net_state: Res<NetworkState>,
-    player_registry: Res<PlayerRegistry>,
-    player_query: Query<(&Transform, Entity), With<crate::player::Player>>,
+    mut net_state: ResMut<NetworkState>,
+    mut player_registry: ResMut<PlayerRegistry>,
+    player_query: Query<&Transform, With<crate::player::Player>>,
 ) {
-    if net_state.mode == NetworkMode::None {
+    if net_state.mode == NetworkMode::None || net_state.socket.is_none() {
         return;
     }
     
-    let socket = match &net_state.socket {
-        Some(s) => s,
-        None => return,
+    let Ok(transform) = player_query.get_single() else {
+        return;
+    };
+
+    if !should_send_update(
+        net_state.last_update_sent.elapsed(),
+        net_state.update_rate,
+        net_state.last_sent_transform,
+        transform.translation,
+        transform.rotation,
+    ) {
+        return;
+    }
+
+    net_state.last_update_sent = Instant::now();
+    net_state.last_sent_transform = Some((transform.translation, transform.rotation));
+
+    let base = if net_state.use_delta_updates { net_state.delta_base } else { None };
+    let (msg, base) = encode_player_update(
+        net_state.local_player_id,
+        base,
+        net_state.updates_since_keyframe,
+        transform.translation,
+        transform.rotation,
+    );
+
+    net_state.delta_base = Some(base);
+    net_state.updates_since_keyframe = match msg {
+        NetworkMessage::PlayerDelta { .. } => net_state.updates_since_keyframe + 1,
+        _ => 0,
     };
     
-    for (transform, _) in player_query.iter() {
-        let msg = NetworkMessage::PlayerUpdate {
-            player_id: net_state.local_player_id,
-            position: transform.translation,
-            rotation: transform.rotation,
-        };
-        
-        if net_state.mode == NetworkMode::Server {
-            let data = bincode::serialize(&msg).unwrap();
-            for (id, client_addr) in player_registry.client_addresses.iter() {
-                if *id != net_state.local_player_id {
-                    let _ = socket.send_to(&data, client_addr);
-                }
-            }
-        } else {
-            let _ = net_state.send_message(&msg);
-        }
+    if net_state.mode == NetworkMode::Server {
+        player_registry.record_position(net_state.local_player_id, now_millis(), transform.translation);
+        let others = player_registry.client_addresses.iter()
+            .filter(|(id, _)| **id != net_state.local_player_id)
+            .map(|(_, client_addr)| client_addr);
+        net_state.send_to_all(&msg, others);
+    } else {
+        let _ = net_state.send_message(&msg);
     }
 }
 
@@ -432,12 +1093,391 @@ fn send_ping(mut net_state: ResMut<NetworkState>) {
         return;
     }
     
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_millis();
+    let timestamp = now_millis();
     
     let msg = NetworkMessage::Ping { timestamp };
     let _ = net_state.send_message(&msg);
     net_state.last_ping_sent = Instant::now();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    const RECV_WAIT: Duration = Duration::from_millis(250);
+
+    fn network_world(mode: NetworkMode) -> (World, SocketAddr) {
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        socket.set_read_timeout(Some(RECV_WAIT)).unwrap();
+        let addr = socket.local_addr().unwrap();
+
+        let mut world = World::new();
+        world.insert_resource(NetworkState { mode, socket: Some(Arc::new(socket)), ..default() });
+        world.init_resource::<GameConfig>();
+        world.init_resource::<ServerList>();
+        world.init_resource::<PlayerRegistry>();
+        world.init_resource::<Events<NetworkEvent>>();
+        (world, addr)
+    }
+
+    fn peer() -> UdpSocket {
+        UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap()
+    }
+
+    fn deliver(world: &mut World, to: SocketAddr, from: &UdpSocket, msg: &NetworkMessage) {
+        from.send_to(&bincode::serialize(msg).unwrap(), to).unwrap();
+        world.run_system_once(handle_network_events).unwrap();
+    }
+
+    fn received_marks(world: &World) -> Vec<u64> {
+        world.resource::<Events<NetworkEvent>>()
+            .iter_current_update_events()
+            .filter_map(|event| match event {
+                NetworkEvent::DrawMarkReceived(mark) => Some(mark.mark_id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn register(registry: &mut PlayerRegistry, msg: &NetworkMessage) -> Option<Vec3> {
+        match *msg {
+            NetworkMessage::PlayerKeyframe { player_id, keyframe, position, .. } => {
+                registry.delta_bases.insert(player_id, (keyframe, position));
+                Some(position)
+            }
+            NetworkMessage::PlayerDelta { player_id, keyframe, delta, .. } => registry.resolve_delta(player_id, keyframe, delta),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn join_target_uses_advertised_port_and_packet_source() {
+        let source: SocketAddr = "192.168.1.20:40000".parse().unwrap();
+        assert_eq!(join_target(source, None, 7879), "192.168.1.20:7879".parse().unwrap());
+
+        let bound: IpAddr = "10.0.0.5".parse().unwrap();
+        assert_eq!(join_target(source, Some(bound), 7879), "10.0.0.5:7879".parse().unwrap());
+    }
+
+    fn reassemble(buffer: &mut FragmentBuffer, from: SocketAddr, fragments: Vec<NetworkMessage>) -> Option<Vec<u8>> {
+        fragments.into_iter().rev().find_map(|fragment| match fragment {
+            NetworkMessage::Fragment { id, index, total, bytes } => buffer.insert(from, id, index, total, bytes),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn fragments_reassemble_in_any_order() {
+        let from: SocketAddr = "127.0.0.1:7878".parse().unwrap();
+        let data: Vec<u8> = (0..100_000).map(|i| i as u8).collect();
+        let mut buffer = FragmentBuffer::default();
+
+        assert_eq!(reassemble(&mut buffer, from, fragment_message(9, &data, FRAGMENT_PAYLOAD_SIZE)), Some(data));
+        assert!(buffer.pending.is_empty());
+    }
+
+    #[test]
+    fn oversized_fragment_totals_are_rejected() {
+        let from: SocketAddr = "127.0.0.1:7878".parse().unwrap();
+        let mut buffer = FragmentBuffer::default();
+
+        assert_eq!(buffer.insert(from, 1, 0, u16::MAX, vec![0; 16]), None);
+        assert_eq!(buffer.insert(from, 1, 0, MAX_FRAGMENTS + 1, vec![0; 16]), None);
+        assert!(buffer.pending.is_empty());
+    }
+
+    #[test]
+    fn pending_messages_are_capped() {
+        let from: SocketAddr = "127.0.0.1:7878".parse().unwrap();
+        let mut buffer = FragmentBuffer::default();
+
+        for id in 0..(MAX_PENDING_MESSAGES as u32 + 10) {
+            buffer.insert(from, id, 0, 2, vec![0; 16]);
+        }
+
+        assert_eq!(buffer.pending.len(), MAX_PENDING_MESSAGES);
+    }
+
+    #[test]
+    fn seen_marks_reject_duplicates_and_forget_the_oldest() {
+        let mut seen = SeenMarks::default();
+        assert!(seen.insert(0));
+        assert!(!seen.insert(0));
+
+        for mark_id in 1..=SEEN_MARK_CAPACITY as u64 {
+            assert!(seen.insert(mark_id));
+        }
+
+        assert_eq!(seen.ids.len(), SEEN_MARK_CAPACITY);
+        assert!(!seen.insert(SEEN_MARK_CAPACITY as u64));
+        assert!(seen.insert(0));
+    }
+
+    #[test]
+    fn segment_distance_handles_crossing_and_parallel_segments() {
+        let crossing = segment_distance(Vec3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, -1.0, 0.5), Vec3::new(0.0, 1.0, 0.5));
+        let parallel = segment_distance(Vec3::ZERO, Vec3::X, Vec3::new(0.0, 2.0, 0.0), Vec3::new(1.0, 2.0, 0.0));
+        let past_end = segment_distance(Vec3::ZERO, Vec3::X, Vec3::new(3.0, 0.0, 0.0), Vec3::new(3.0, 1.0, 0.0));
+
+        assert!((crossing - 0.5).abs() < 1e-5);
+        assert!((parallel - 2.0).abs() < 1e-5);
+        assert!((past_end - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn blocking_player_is_resolved_at_the_rewound_time() {
+        let mut registry = PlayerRegistry::default();
+        registry.record_position(2, 1000, Vec3::ZERO);
+        registry.record_position(2, 1200, Vec3::new(5.0, 0.0, 0.0));
+        registry.record_position(1, 1000, Vec3::new(0.0, 0.0, -6.0));
+
+        let dimensions = PlayerDimensions::default();
+        let (origin, target) = (Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 5.0));
+
+        assert_eq!(registry.blocking_player(1, origin, target, 1000, &dimensions), Some(2));
+        assert_eq!(registry.blocking_player(1, origin, target, 1200, &dimensions), None);
+        assert_eq!(registry.blocking_player(2, origin, target, 1000, &dimensions), None);
+    }
+
+    #[test]
+    fn rewind_is_capped() {
+        assert_eq!(rewind_time(10_000, 150), 9_850);
+        assert_eq!(rewind_time(10_000, u32::MAX), 10_000 - MAX_REWIND_MS);
+        assert_eq!(rewind_time(100, 150), 0);
+    }
+
+    #[test]
+    fn position_history_interpolates_between_snapshots() {
+        let mut history = PositionHistory::default();
+        history.record(1000, Vec3::ZERO);
+        history.record(1100, Vec3::new(10.0, 0.0, 0.0));
+
+        assert_eq!(history.position_at(900), Some(Vec3::ZERO));
+        assert!(history.position_at(1050).unwrap().abs_diff_eq(Vec3::new(5.0, 0.0, 0.0), 1e-5));
+        assert_eq!(history.position_at(2000), Some(Vec3::new(10.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn updates_with_non_finite_components_leave_the_registry_unchanged() {
+        let mut registry = PlayerRegistry::default();
+        assert!(registry.apply_update(4, Vec3::ONE, Quat::IDENTITY, 1000));
+
+        assert!(!registry.apply_update(4, Vec3::new(f32::NAN, 0.0, 0.0), Quat::IDENTITY, 1100));
+        assert!(!registry.apply_update(4, Vec3::ZERO, Quat::from_xyzw(0.0, f32::INFINITY, 0.0, 1.0), 1100));
+        assert!(!registry.apply_update(5, Vec3::ZERO, Quat::from_xyzw(0.0, 0.0, 0.0, 0.0), 1100));
+
+        assert_eq!(registry.players[&4].position, Vec3::ONE);
+        assert_eq!(registry.position_at(4, 1100), Some(Vec3::ONE));
+        assert!(!registry.players.contains_key(&5));
+    }
+
+    #[test]
+    fn config_defaults_to_global_broadcast_on_any_interface() {
+        let config = NetworkConfig::from_args(&[]);
+        assert_eq!(config, NetworkConfig::default());
+        assert_eq!(config.announce_target(), SocketAddr::new(IpAddr::V4(Ipv4Addr::BROADCAST), DISCOVERY_PORT));
+    }
+
+    #[test]
+    fn configured_subnet_broadcast_is_used_for_discovery_and_announce() {
+        let args: Vec<String> = ["lspire", "--bind", "192.168.1.5", "--broadcast", "192.168.1.255"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let config = NetworkConfig::from_args(&args);
+        let subnet: IpAddr = "192.168.1.255".parse().unwrap();
+
+        assert_eq!(config.bind_address, "192.168.1.5".parse::<IpAddr>().unwrap());
+        assert_eq!(config.announce_target(), SocketAddr::new(subnet, DISCOVERY_PORT));
+        assert!(config.discovery_targets().all(|target| target.ip() == subnet && target.port() != DISCOVERY_PORT));
+        assert_eq!(config.discovery_targets().count(), SERVER_PORTS.filter(|port| *port != DISCOVERY_PORT).count());
+    }
+
+    #[test]
+    fn listening_sockets_use_the_wildcard_so_broadcasts_arrive() {
+        let config = NetworkConfig { bind_address: "192.168.1.5".parse().unwrap(), ..default() };
+        assert!(config.listen_address().is_unspecified());
+        assert!(config.listen_address().is_ipv4());
+    }
+
+    #[test]
+    fn first_update_is_a_keyframe() {
+        let (msg, base) = encode_player_update(3, None, 0, Vec3::new(1.0, 2.0, 3.0), Quat::IDENTITY);
+        assert!(matches!(msg, NetworkMessage::PlayerKeyframe { player_id: 3, keyframe: 0, .. }));
+        assert_eq!(base, (0, Vec3::new(1.0, 2.0, 3.0)));
+    }
+
+    #[test]
+    fn deltas_are_relative_to_the_keyframe_so_loss_does_not_drift() {
+        let mut receiver = PlayerRegistry::default();
+        let (keyframe, mut base) = encode_player_update(1, None, 0, Vec3::ZERO, Quat::IDENTITY);
+        register(&mut receiver, &keyframe);
+
+        let mut last_received = None;
+        for (since_keyframe, step) in (1..10).enumerate() {
+            let position = Vec3::new(step as f32 * 0.25, 0.0, 0.0);
+            let (msg, next_base) = encode_player_update(1, Some(base), since_keyframe as u32, position, Quat::IDENTITY);
+            base = next_base;
+
+            if step % 3 != 0 {
+                last_received = register(&mut receiver, &msg);
+                assert!(last_received.unwrap().abs_diff_eq(position, DELTA_UNIT));
+            }
+        }
+
+        assert!(last_received.unwrap().abs_diff_eq(Vec3::new(2.0, 0.0, 0.0), DELTA_UNIT));
+    }
+
+    #[test]
+    fn deltas_for_an_unknown_keyframe_are_dropped() {
+        let mut receiver = PlayerRegistry::default();
+        receiver.delta_bases.insert(1, (4, Vec3::ZERO));
+
+        assert_eq!(receiver.resolve_delta(1, 5, [100, 0, 0]), None);
+        assert_eq!(receiver.resolve_delta(2, 4, [100, 0, 0]), None);
+        assert!(receiver.resolve_delta(1, 4, [100, 0, 0]).unwrap().abs_diff_eq(Vec3::new(0.1, 0.0, 0.0), 1e-6));
+    }
+
+    #[test]
+    fn keyframe_interval_and_overflow_force_keyframes() {
+        let base = Some((7, Vec3::ZERO));
+        let (msg, next) = encode_player_update(1, base, KEYFRAME_INTERVAL, Vec3::X, Quat::IDENTITY);
+        assert!(matches!(msg, NetworkMessage::PlayerKeyframe { keyframe: 8, .. }));
+        assert_eq!(next, (8, Vec3::X));
+
+        let (msg, _) = encode_player_update(1, base, 0, Vec3::new(100.0, 0.0, 0.0), Quat::IDENTITY);
+        assert!(matches!(msg, NetworkMessage::PlayerKeyframe { .. }));
+    }
+
+    #[test]
+    fn draw_marks_are_clamped_into_the_texture() {
+        let mark = DrawMark { mark_id: 1, surface_id: 2, uv: Vec2::new(-0.5, 3.0), shade: 4, size: 10.0 };
+        let clamped = mark.clamped();
+        assert_eq!(clamped.uv, Vec2::new(0.0, 1.0));
+        assert_eq!(clamped.size, MAX_MARK_SIZE);
+        assert_eq!((clamped.mark_id, clamped.surface_id, clamped.shade), (1, 2, 4));
+
+        let broken = DrawMark { uv: Vec2::new(f32::NAN, 0.5), size: f32::INFINITY, ..mark }.clamped();
+        assert_eq!(broken.uv, Vec2::ZERO);
+        assert_eq!(broken.size, 0.0);
+
+        let valid = DrawMark { uv: Vec2::new(0.25, 0.75), size: 0.1, ..mark };
+        assert_eq!(valid.clamped(), valid);
+    }
+
+    #[test]
+    fn binding_skips_ports_that_are_taken() {
+        let loopback = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let taken = UdpSocket::bind((loopback, 0)).unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        let error = bind_first_available(loopback, port..port + 1).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AddrInUse);
+
+        let (socket, bound) = bind_first_available(loopback, port..port.saturating_add(8)).unwrap();
+        assert_ne!(bound, port);
+        assert_eq!(socket.local_addr().unwrap().port(), bound);
+    }
+
+    #[test]
+    fn updates_are_throttled_and_skip_unchanged_transforms() {
+        let moved = Some((Vec3::ZERO, Quat::IDENTITY));
+        let tick = Duration::from_millis(50);
+
+        assert!(!should_send_update(Duration::from_millis(10), 20.0, moved, Vec3::X, Quat::IDENTITY));
+        assert!(should_send_update(tick, 20.0, moved, Vec3::X, Quat::IDENTITY));
+        assert!(should_send_update(tick, 20.0, moved, Vec3::ZERO, Quat::from_rotation_y(0.5)));
+        assert!(!should_send_update(tick, 20.0, moved, Vec3::ZERO, Quat::IDENTITY));
+        assert!(should_send_update(UPDATE_KEEPALIVE, 20.0, moved, Vec3::ZERO, Quat::IDENTITY));
+        assert!(should_send_update(tick, 20.0, None, Vec3::ZERO, Quat::IDENTITY));
+    }
+
+    #[test]
+    fn quantized_deltas_stay_within_half_a_unit() {
+        for delta in [Vec3::ZERO, Vec3::new(0.0123, -1.5, 7.77777), Vec3::splat(-32.0)] {
+            let restored = dequantize_delta(quantize_delta(delta).unwrap());
+            assert!((restored - delta).abs().max_element() <= DELTA_UNIT / 2.0 + 1e-6, "{delta} -> {restored}");
+        }
+
+        assert_eq!(quantize_delta(Vec3::new(40.0, 0.0, 0.0)), None);
+        assert_eq!(quantize_delta(Vec3::new(0.0, f32::NAN, 0.0)), None);
+    }
+
+    #[test]
+    fn join_accept_adopts_the_server_seed() {
+        let (mut world, client) = network_world(NetworkMode::Client);
+        world.insert_resource(GameConfig { seed: 1 });
+
+        let accept = NetworkMessage::JoinAccept {
+            player_id: 2,
+            world_seed: 1234,
+            spawn_position: Vec3::new(1.0, 2.0, 3.0),
+            existing_players: vec![(1, Vec3::ZERO, Quat::IDENTITY)],
+        };
+        deliver(&mut world, client, &peer(), &accept);
+
+        assert_eq!(world.resource::<GameConfig>().seed, 1234);
+        let net_state = world.resource::<NetworkState>();
+        assert_eq!(net_state.local_player_id, 2);
+        assert_eq!(net_state.assigned_spawn, Some(Vec3::new(1.0, 2.0, 3.0)));
+        assert!(world.resource::<PlayerRegistry>().players.contains_key(&1));
+    }
+
+    #[test]
+    fn ping_stats_summarize_the_history() {
+        assert_eq!(ping_stats(&[]), None);
+        assert_eq!(ping_stats(&[30.0]), Some(PingStats { min: 30.0, avg: 30.0, max: 30.0, jitter: 0.0 }));
+
+        let stats = ping_stats(&[20.0, 40.0, 30.0, 30.0]).unwrap();
+        assert_eq!((stats.min, stats.avg, stats.max), (20.0, 30.0, 40.0));
+        assert!((stats.jitter - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejected_marks_do_not_burn_their_id() {
+        let (mut world, server) = network_world(NetworkMode::Server);
+        let (painter, other, stranger) = (peer(), peer(), peer());
+        {
+            let mut registry = world.resource_mut::<PlayerRegistry>();
+            registry.client_addresses.insert(1, painter.local_addr().unwrap());
+            registry.client_addresses.insert(2, other.local_addr().unwrap());
+            registry.record_position(2, now_millis(), Vec3::ZERO);
+        }
+
+        let mark = DrawMark { mark_id: mark_id(1, 1), surface_id: 3, uv: Vec2::splat(0.5), shade: 0, size: 0.1 };
+        let draw = |origin: Vec3, point: Vec3| NetworkMessage::DrawMark { mark, origin, point, rewind_ms: 0 };
+        let blocked = draw(Vec3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 5.0));
+        let clear = draw(Vec3::new(10.0, 0.0, -5.0), Vec3::new(10.0, 0.0, 5.0));
+
+        deliver(&mut world, server, &stranger, &clear);
+        deliver(&mut world, server, &other, &clear);
+        deliver(&mut world, server, &painter, &blocked);
+        assert!(received_marks(&world).is_empty());
+
+        deliver(&mut world, server, &painter, &clear);
+        assert_eq!(received_marks(&world), vec![mark.mark_id]);
+
+        deliver(&mut world, server, &painter, &clear);
+        assert_eq!(received_marks(&world), vec![mark.mark_id]);
+    }
+
+    #[test]
+    fn keyframes_from_another_address_are_ignored() {
+        let (mut world, server) = network_world(NetworkMode::Server);
+        let (owner, spoofer) = (peer(), peer());
+        world.resource_mut::<PlayerRegistry>().client_addresses.insert(1, owner.local_addr().unwrap());
+
+        let keyframe = |keyframe: u16, position: Vec3| NetworkMessage::PlayerKeyframe { player_id: 1, keyframe, position, rotation: Quat::IDENTITY };
+        deliver(&mut world, server, &owner, &keyframe(4, Vec3::new(1.0, 2.0, 3.0)));
+        deliver(&mut world, server, &spoofer, &keyframe(5, Vec3::new(50.0, 0.0, 0.0)));
+
+        let registry = world.resource::<PlayerRegistry>();
+        assert_eq!(registry.delta_bases.get(&1), Some(&(4, Vec3::new(1.0, 2.0, 3.0))));
+        assert_eq!(registry.players[&1].position, Vec3::new(1.0, 2.0, 3.0));
+
+        let delta = NetworkMessage::PlayerDelta { player_id: 1, keyframe: 4, delta: [1000, 0, 0], rotation: Quat::IDENTITY };
+        deliver(&mut world, server, &owner, &delta);
+        assert!(world.resource::<PlayerRegistry>().players[&1].position.abs_diff_eq(Vec3::new(2.0, 2.0, 3.0), 1e-5));
+    }
+}
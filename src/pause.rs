@@ -0,0 +1,243 @@
+use bevy::prelude::*;
+use bevy::window::CursorGrabMode;
+use bevy_rapier3d::prelude::*;
+use crate::camera::CursorGrabbed;
+use crate::menu::GameState;
+use crate::network::{NetworkState, PlayerRegistry};
+use crate::settings::{spawn_setting_rows, ChangedButton, Settings, ThemedButton, UiTheme, BUTTON_BORDER_WIDTH};
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Paused>()
+            .init_resource::<PauseView>()
+            .add_systems(OnEnter(GameState::InGame), reset_pause)
+            .add_systems(OnExit(GameState::InGame), (reset_pause, cleanup_pause_menu))
+            .add_systems(Update, (
+                toggle_pause,
+                pause_button_system,
+                pause_action,
+                apply_pause,
+                update_pause_menu,
+            ).chain().run_if(in_state(GameState::InGame)));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Paused(pub bool);
+
+#[derive(Component)]
+struct PauseUI;
+
+#[derive(Component)]
+enum PauseButton {
+    Resume,
+    Settings,
+    CloseSettings,
+    QuitToMenu,
+}
+
+#[derive(Resource, Clone, Copy, PartialEq, Default)]
+enum PauseView {
+    #[default]
+    Main,
+    Settings,
+}
+
+const NORMAL_BUTTON: Color = Color::srgba(0.15, 0.15, 0.15, 0.9);
+
+fn reset_pause(
+    mut paused: ResMut<Paused>,
+    mut pause_view: ResMut<PauseView>,
+    mut rapier_config: Query<&mut RapierConfiguration>,
+) {
+    paused.0 = false;
+    *pause_view = PauseView::Main;
+
+    for mut config in rapier_config.iter_mut() {
+        config.physics_pipeline_active = true;
+    }
+}
+
+fn toggle_pause(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut paused: ResMut<Paused>,
+    mut pause_view: ResMut<PauseView>,
+) {
+    if keyboard.just_pressed(KeyCode::Escape) {
+        paused.0 = !paused.0;
+        *pause_view = PauseView::Main;
+    }
+}
+
+fn apply_pause(
+    paused: Res<Paused>,
+    mut cursor_grabbed: ResMut<CursorGrabbed>,
+    mut windows: Query<&mut Window>,
+    mut rapier_config: Query<&mut RapierConfiguration>,
+) {
+    if !paused.is_changed() {
+        return;
+    }
+
+    for mut config in rapier_config.iter_mut() {
+        config.physics_pipeline_active = !paused.0;
+    }
+
+    cursor_grabbed.0 = !paused.0;
+    for mut window in windows.iter_mut() {
+        if paused.0 {
+            window.cursor_options.grab_mode = CursorGrabMode::None;
+            window.cursor_options.visible = true;
+        } else if window.focused {
+            window.cursor_options.grab_mode = CursorGrabMode::Locked;
+            window.cursor_options.visible = false;
+        }
+    }
+}
+
+fn update_pause_menu(
+    mut commands: Commands,
+    paused: Res<Paused>,
+    pause_view: Res<PauseView>,
+    settings: Res<Settings>,
+    pause_query: Query<Entity, With<PauseUI>>,
+) {
+    if !paused.is_changed() && !pause_view.is_changed() {
+        return;
+    }
+
+    for entity in &pause_query {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    if paused.0 {
+        spawn_pause_menu(&mut commands, *pause_view, &settings);
+    }
+}
+
+fn spawn_pause_menu(commands: &mut Commands, view: PauseView, settings: &Settings) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            PauseUI,
+        ))
+        .with_children(|parent| {
+            let title = if view == PauseView::Settings { "SETTINGS" } else { "PAUSED" };
+
+            parent.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 60.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                },
+            ));
+
+            match view {
+                PauseView::Main => {
+                    spawn_button(parent, "Resume", PauseButton::Resume);
+                    spawn_button(parent, "Settings", PauseButton::Settings);
+                    spawn_button(parent, "Quit to Menu", PauseButton::QuitToMenu);
+                }
+                PauseView::Settings => {
+                    spawn_setting_rows(parent, settings);
+                    spawn_button(parent, "Back", PauseButton::CloseSettings);
+                }
+            }
+        });
+}
+
+fn spawn_button(parent: &mut ChildBuilder, text: &str, button_type: PauseButton) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(250.0),
+                height: Val::Px(65.0),
+                margin: UiRect::all(Val::Px(10.0)),
+                border: UiRect::all(Val::Px(BUTTON_BORDER_WIDTH)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON),
+            BorderColor(Color::NONE),
+            button_type,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(text),
+                TextFont {
+                    font_size: 33.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn pause_button_system(
+    theme: Res<UiTheme>,
+    mut interaction_query: Query<ThemedButton, ChangedButton>,
+) {
+    for (interaction, mut color, mut border) in &mut interaction_query {
+        theme.style_button(*interaction, &mut color, &mut border);
+    }
+}
+
+fn pause_action(
+    interaction_query: Query<(&Interaction, &PauseButton), ChangedButton>,
+    mut paused: ResMut<Paused>,
+    mut pause_view: ResMut<PauseView>,
+    mut net_state: ResMut<NetworkState>,
+    mut player_registry: ResMut<PlayerRegistry>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            PauseButton::Resume => {
+                paused.0 = false;
+            }
+            PauseButton::Settings => {
+                *pause_view = PauseView::Settings;
+            }
+            PauseButton::CloseSettings => {
+                *pause_view = PauseView::Main;
+            }
+            PauseButton::QuitToMenu => {
+                net_state.disconnect(&player_registry);
+                player_registry.clear();
+                next_state.set(GameState::Menu);
+            }
+        }
+    }
+}
+
+fn cleanup_pause_menu(
+    mut commands: Commands,
+    pause_query: Query<Entity, With<PauseUI>>,
+) {
+    for entity in &pause_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use crate::menu::GameState;
+use crate::player::Player;
 
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum GameSystemSet {
@@ -14,6 +15,10 @@ pub struct PhysicsPlugin;
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+            .init_resource::<CollisionRules>()
+            .init_resource::<PhysicsTuning>()
+            .add_systems(Update, apply_physics_tuning)
+            .add_systems(Update, apply_collision_rules.run_if(resource_changed::<CollisionRules>))
             .configure_sets(Update, (
                 GameSystemSet::Input,
                 GameSystemSet::Physics,
@@ -21,3 +26,95 @@ impl Plugin for PhysicsPlugin {
             ).chain().run_if(in_state(GameState::InGame)));
     }
 }
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct PhysicsTuning {
+    pub gravity: f32,
+    pub jump_force: f32,
+    pub air_jump_force: f32,
+}
+
+impl Default for PhysicsTuning {
+    fn default() -> Self {
+        Self {
+            gravity: -9.81,
+            jump_force: 6.0,
+            air_jump_force: 5.5,
+        }
+    }
+}
+
+fn apply_physics_tuning(
+    tuning: Res<PhysicsTuning>,
+    mut rapier_config: Query<&mut RapierConfiguration>,
+) {
+    let gravity = Vec3::Y * tuning.gravity;
+    for mut config in rapier_config.iter_mut() {
+        if config.gravity != gravity {
+            config.gravity = gravity;
+        }
+    }
+}
+
+pub const WORLD_GROUP: Group = Group::GROUP_1;
+pub const PLAYER_GROUP: Group = Group::GROUP_2;
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct CollisionRules {
+    pub player_vs_player: bool,
+}
+
+pub fn player_collision_groups(player_vs_player: bool) -> CollisionGroups {
+    let filters = if player_vs_player {
+        WORLD_GROUP | PLAYER_GROUP
+    } else {
+        WORLD_GROUP
+    };
+    CollisionGroups::new(PLAYER_GROUP, filters)
+}
+
+pub fn world_collision_groups() -> CollisionGroups {
+    CollisionGroups::new(WORLD_GROUP, Group::ALL)
+}
+
+fn apply_collision_rules(
+    rules: Res<CollisionRules>,
+    mut player_query: Query<&mut CollisionGroups, With<Player>>,
+) {
+    for mut groups in player_query.iter_mut() {
+        *groups = player_collision_groups(rules.player_vs_player);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn interacts(a: CollisionGroups, b: CollisionGroups) -> bool {
+        a.memberships.intersects(b.filters) && b.memberships.intersects(a.filters)
+    }
+
+    #[test]
+    fn players_pass_through_each_other_but_not_the_world() {
+        let world = world_collision_groups();
+        let ghost = player_collision_groups(false);
+        let solid = player_collision_groups(true);
+
+        assert!(interacts(ghost, world));
+        assert!(!interacts(ghost, ghost));
+        assert!(!interacts(ghost, solid));
+        assert!(interacts(solid, solid));
+        assert!(interacts(solid, world));
+    }
+
+    #[test]
+    fn tuned_gravity_reaches_the_rapier_config() {
+        let mut world = World::new();
+        world.insert_resource(PhysicsTuning { gravity: -20.0, ..default() });
+        let config = world.spawn(RapierConfiguration::new(1.0)).id();
+        world.run_system_once(apply_physics_tuning).unwrap();
+
+        assert_eq!(world.get::<RapierConfiguration>(config).unwrap().gravity, Vec3::new(0.0, -20.0, 0.0));
+    }
+}
@@ -1,16 +1,24 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
-use crate::physics::GameSystemSet;
+use crate::camera::spectating;
+use crate::physics::{player_collision_groups, CollisionRules, GameSystemSet, PhysicsTuning};
 use crate::menu::GameState;
+use crate::network::NetworkState;
+use crate::pause::Paused;
+use crate::world::{SpawnPoints, SurfaceKind, WorldRules};
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::InGame), spawn_player)
+        app.init_resource::<MovementTuning>()
+            .add_event::<PlayerEvent>()
+            .add_systems(OnEnter(GameState::InGame), spawn_player)
+            .add_systems(OnExit(GameState::InGame), despawn_player)
             .add_systems(Update, (
-                handle_speed_control,
-                player_movement,
+                handle_speed_control.run_if(not(spectating)),
+                (detect_ground, player_movement, update_wall_run).chain().run_if(not(spectating)),
+                update_stamina,
                 check_death,
             ).in_set(GameSystemSet::Input).run_if(in_state(GameState::InGame)));
     }
@@ -19,6 +27,15 @@ impl Plugin for PlayerPlugin {
 #[derive(Component)]
 pub struct Player;
 
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub enum PlayerEvent {
+    Jumped { air_jump: bool },
+    Landed { impact_speed: f32 },
+    SprintStarted,
+    SprintEnded,
+    DriftStarted,
+}
+
 #[derive(Component)]
 pub struct SpawnPoint(pub Vec3);
 
@@ -34,6 +51,36 @@ pub struct PlayerMovement {
     pub velocity: Vec3,
     pub drift_factor: f32,
     pub is_braking: bool,
+    pub is_grounded: bool,
+    pub ground_surface: Option<SurfaceKind>,
+    pub fall_speed: f32,
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct PlayerDimensions {
+    pub half_height: f32,
+    pub radius: f32,
+    pub eye_offset: f32,
+}
+
+impl Default for PlayerDimensions {
+    fn default() -> Self {
+        Self {
+            half_height: 0.5,
+            radius: 0.3,
+            eye_offset: 1.6,
+        }
+    }
+}
+
+impl PlayerDimensions {
+    pub fn collider(&self) -> Collider {
+        Collider::capsule_y(self.half_height, self.radius)
+    }
+
+    pub fn ground_ray(&self, position: Vec3) -> (Vec3, f32) {
+        (position - Vec3::Y * self.half_height, self.radius + 0.1)
+    }
 }
 
 #[derive(Component)]
@@ -42,6 +89,120 @@ pub struct JumpState {
     pub max_jumps: u8,
 }
 
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct WallRun {
+    pub active: bool,
+    pub timer: f32,
+    pub cooldown: f32,
+    pub wall_normal: Vec3,
+}
+
+pub const WALL_RUN_DURATION: f32 = 1.2;
+pub const WALL_RUN_COOLDOWN: f32 = 0.8;
+pub const WALL_RUN_GRAVITY_SCALE: f32 = 0.15;
+pub const WALL_RUN_MIN_SPEED: f32 = 4.0;
+pub const WALL_KICK_SPEED: f32 = 6.0;
+
+impl WallRun {
+    pub fn can_attach(&self) -> bool {
+        !self.active && self.cooldown <= 0.0
+    }
+
+    pub fn attach(&mut self, wall_normal: Vec3) {
+        self.active = true;
+        self.timer = WALL_RUN_DURATION;
+        self.wall_normal = wall_normal;
+    }
+
+    pub fn detach(&mut self) {
+        self.active = false;
+        self.timer = 0.0;
+        self.cooldown = WALL_RUN_COOLDOWN;
+    }
+
+    pub fn tick(&mut self, dt: f32) {
+        if self.active {
+            self.timer -= dt;
+        } else {
+            self.cooldown = (self.cooldown - dt).max(0.0);
+        }
+    }
+}
+
+pub fn wall_probe_directions(velocity: Vec3) -> Option<[Vec3; 2]> {
+    let horizontal = Vec3::new(velocity.x, 0.0, velocity.z);
+    if horizontal.length_squared() < 0.0001 {
+        return None;
+    }
+
+    let side = horizontal.normalize().cross(Vec3::Y);
+    Some([side, -side])
+}
+
+pub fn is_wall_normal(normal: Vec3) -> bool {
+    normal.y.abs() < 0.3
+}
+
+#[derive(Component)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    pub drain_rate: f32,
+    pub regen_rate: f32,
+    pub sprint_speed_ratio: f32,
+    pub is_sprinting: bool,
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self {
+            current: 100.0,
+            max: 100.0,
+            drain_rate: 15.0,
+            regen_rate: 20.0,
+            sprint_speed_ratio: 0.5,
+            is_sprinting: false,
+        }
+    }
+}
+
+impl Stamina {
+    pub fn ratio(&self) -> f32 {
+        (self.current / self.max).clamp(0.0, 1.0)
+    }
+
+    pub fn update(&mut self, is_sprinting: bool, dt: f32) -> Option<PlayerEvent> {
+        self.current = if is_sprinting {
+            self.current - self.drain_rate * dt
+        } else {
+            self.current + self.regen_rate * dt
+        }
+        .clamp(0.0, self.max);
+
+        if is_sprinting == self.is_sprinting {
+            return None;
+        }
+
+        self.is_sprinting = is_sprinting;
+        Some(if is_sprinting {
+            PlayerEvent::SprintStarted
+        } else {
+            PlayerEvent::SprintEnded
+        })
+    }
+}
+
+pub fn track_landing(fall_speed: f32, was_grounded: bool, is_grounded: bool, vertical_velocity: f32) -> (f32, Option<PlayerEvent>) {
+    let fall_speed = fall_speed.max(-vertical_velocity).max(0.0);
+
+    if !is_grounded {
+        return (fall_speed, None);
+    }
+
+    let landed = (!was_grounded).then_some(PlayerEvent::Landed { impact_speed: fall_speed });
+    (0.0, landed)
+}
+
 impl Default for PlayerSpeed {
     fn default() -> Self {
         Self {
@@ -52,24 +213,43 @@ impl Default for PlayerSpeed {
     }
 }
 
-fn spawn_player(mut commands: Commands) {
-    let spawn_position = Vec3::new(0.0, 2.0, 0.0);
-    
+fn spawn_player(
+    mut commands: Commands,
+    spawn_points: Res<SpawnPoints>,
+    collision_rules: Res<CollisionRules>,
+    net_state: Res<NetworkState>,
+) {
+    let spawn_position = net_state
+        .assigned_spawn
+        .or_else(|| spawn_points.0.first().copied())
+        .unwrap_or(Vec3::new(0.0, 2.0, 0.0));
+    let dimensions = PlayerDimensions::default();
+
     commands.spawn((
         Player,
         SpawnPoint(spawn_position),
+        dimensions,
         PlayerSpeed::default(),
+        Stamina::default(),
         PlayerMovement {
             velocity: Vec3::ZERO,
             drift_factor: 0.0,
             is_braking: false,
+            is_grounded: false,
+            ground_surface: None,
+            fall_speed: 0.0,
         },
         JumpState {
             jumps_remaining: 1,
             max_jumps: 2,
         },
+        WallRun::default(),
+        Transform::from_xyz(spawn_position.x, spawn_position.y, spawn_position.z),
+        Visibility::Hidden,
+    )).insert((
         RigidBody::Dynamic,
-        Collider::capsule_y(0.5, 0.3),
+        dimensions.collider(),
+        player_collision_groups(collision_rules.player_vs_player),
         LockedAxes::ROTATION_LOCKED,
         Velocity::zero(),
         GravityScale(1.0),
@@ -81,15 +261,28 @@ fn spawn_player(mut commands: Commands) {
             coefficient: 0.0,
             combine_rule: CoefficientCombineRule::Min,
         },
-        Transform::from_xyz(spawn_position.x, spawn_position.y, spawn_position.z),
-        Visibility::Hidden,
     ));
 }
 
+fn despawn_player(
+    mut commands: Commands,
+    query: Query<Entity, With<Player>>,
+) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
 fn handle_speed_control(
     mut scroll_events: EventReader<bevy::input::mouse::MouseWheel>,
     mut query: Query<&mut PlayerSpeed, With<Player>>,
+    paused: Res<Paused>,
 ) {
+    if paused.0 {
+        scroll_events.clear();
+        return;
+    }
+
     let Ok(mut speed) = query.get_single_mut() else {
         return;
     };
@@ -100,15 +293,186 @@ fn handle_speed_control(
     }
 }
 
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct MovementTuning {
+    pub drift_threshold: f32,
+    pub drift_exponent: f32,
+    pub drift_grip: f32,
+    pub drift_grip_scale: f32,
+    pub brake_base: f32,
+    pub brake_scale: f32,
+}
+
+impl Default for MovementTuning {
+    fn default() -> Self {
+        Self {
+            drift_threshold: 0.4,
+            drift_exponent: 1.5,
+            drift_grip: 0.03,
+            drift_grip_scale: 0.07,
+            brake_base: 0.25,
+            brake_scale: 0.15,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MovementInput {
+    pub direction: Vec3,
+    pub brake: bool,
+    pub handbrake: bool,
+    pub jump: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlayerSimState {
+    pub velocity: Vec3,
+    pub vertical_velocity: f32,
+    pub drift_factor: f32,
+    pub is_braking: bool,
+    pub is_grounded: bool,
+    pub jumps_remaining: u8,
+    pub max_jumps: u8,
+    pub speed: f32,
+    pub max_speed: f32,
+}
+
+const REFERENCE_FRAME_RATE: f32 = 60.0;
+pub const MAX_STEP_DT: f32 = 0.1;
+pub const MAX_SUBSTEPS: usize = 8;
+
+pub fn substeps(dt: f32) -> impl Iterator<Item = f32> {
+    let count = (dt / MAX_STEP_DT).ceil().clamp(1.0, MAX_SUBSTEPS as f32) as usize;
+    let step = (dt.max(0.0) / count as f32).min(MAX_STEP_DT);
+    std::iter::repeat_n(step, count)
+}
+
+fn frame_lerp_factor(per_frame: f32, dt: f32) -> f32 {
+    1.0 - (1.0 - per_frame).powf(dt * REFERENCE_FRAME_RATE)
+}
+
+pub fn simulate_player_step(
+    state: &mut PlayerSimState,
+    input: MovementInput,
+    tuning: &MovementTuning,
+    physics: &PhysicsTuning,
+    dt: f32,
+) -> Vec<PlayerEvent> {
+    let mut events = Vec::new();
+    let was_drifting = state.drift_factor > 0.0;
+    let has_input = input.direction.length_squared() > 0.0001;
+    let input_direction = if has_input {
+        input.direction.normalize()
+    } else {
+        Vec3::ZERO
+    };
+
+    state.is_braking = input.brake;
+
+    let target_velocity = if input.brake {
+        Vec3::ZERO
+    } else if has_input {
+        input_direction * state.speed
+    } else {
+        Vec3::ZERO
+    };
+
+    let speed_ratio = state.speed / state.max_speed;
+
+    let acceleration_lerp = if input.brake {
+        let current_speed = state.velocity.length();
+        let speed_normalized = (current_speed / state.max_speed).clamp(0.0, 1.0);
+        tuning.brake_base + speed_normalized * tuning.brake_scale
+    } else if input.handbrake {
+        state.drift_factor = 1.0;
+        tuning.drift_grip
+    } else if has_input {
+        if speed_ratio > tuning.drift_threshold {
+            let drift = ((speed_ratio - tuning.drift_threshold) / (1.0 - tuning.drift_threshold))
+                .clamp(0.0, 1.0)
+                .powf(tuning.drift_exponent);
+            state.drift_factor = drift;
+            tuning.drift_grip + drift * tuning.drift_grip_scale
+        } else {
+            state.drift_factor = 0.0;
+            0.15
+        }
+    } else {
+        state.drift_factor = 0.0;
+        0.08
+    };
+
+    state.velocity = state.velocity.lerp(target_velocity, frame_lerp_factor(acceleration_lerp, dt));
+
+    if state.is_grounded {
+        state.jumps_remaining = state.max_jumps - 1;
+    }
+
+    if input.jump {
+        if state.is_grounded {
+            state.vertical_velocity = physics.jump_force;
+            state.jumps_remaining = state.max_jumps - 1;
+            events.push(PlayerEvent::Jumped { air_jump: false });
+        } else if state.jumps_remaining > 0 {
+            state.vertical_velocity = physics.air_jump_force;
+            state.jumps_remaining -= 1;
+            events.push(PlayerEvent::Jumped { air_jump: true });
+        }
+    }
+
+    if !was_drifting && state.drift_factor > 0.0 {
+        events.push(PlayerEvent::DriftStarted);
+    }
+
+    events
+}
+
+fn detect_ground(
+    mut player_query: Query<(Entity, &Transform, &Velocity, &PlayerDimensions, &mut PlayerMovement), With<Player>>,
+    surface_query: Query<&SurfaceKind>,
+    rapier_context: ReadRapierContext,
+    paused: Res<Paused>,
+    mut player_events: EventWriter<PlayerEvent>,
+) {
+    if paused.0 {
+        return;
+    }
+
+    let rapier_context = rapier_context.single();
+
+    let Ok((player_entity, transform, velocity, dimensions, mut movement)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    let (ray_pos, max_toi) = dimensions.ground_ray(transform.translation);
+    let ray_dir = Vec3::NEG_Y;
+    let filter = QueryFilter::default().exclude_rigid_body(player_entity);
+
+    let ground_hit = rapier_context.cast_ray(ray_pos, ray_dir, max_toi, true, filter);
+    let (fall_speed, landed) = track_landing(movement.fall_speed, movement.is_grounded, ground_hit.is_some(), velocity.linvel.y);
+    if let Some(event) = landed {
+        player_events.send(event);
+    }
+    movement.fall_speed = fall_speed;
+    movement.is_grounded = ground_hit.is_some();
+    movement.ground_surface = ground_hit
+        .map(|(entity, _)| surface_query.get(entity).copied().unwrap_or_default());
+}
+
 fn player_movement(
     keyboard: Res<ButtonInput<KeyCode>>,
-    mut player_query: Query<(Entity, &mut Velocity, &PlayerSpeed, &mut PlayerMovement, &Transform, &mut JumpState), With<Player>>,
+    mut player_query: Query<(&mut Velocity, &PlayerSpeed, &mut PlayerMovement, &mut JumpState), With<Player>>,
     camera_query: Query<&Transform, (With<Camera3d>, Without<Player>)>,
-    rapier_context: ReadRapierContext,
+    (tuning, physics): (Res<MovementTuning>, Res<PhysicsTuning>),
+    paused: Res<Paused>,
+    time: Res<Time>,
+    mut player_events: EventWriter<PlayerEvent>,
 ) {
-    let rapier_context = rapier_context.single();
+    if paused.0 {
+        return;
+    }
 
-    let Ok((player_entity, mut velocity, speed, mut movement, transform, mut jump_state)) = player_query.get_single_mut() else {
+    let Ok((mut velocity, speed, mut movement, mut jump_state)) = player_query.get_single_mut() else {
         return;
     };
 
@@ -116,9 +480,6 @@ fn player_movement(
         return;
     };
 
-    let jump_force = 6.0;
-    let double_jump_force = 5.5;
-
     if !camera_transform.rotation.is_finite() {
         return;
     }
@@ -141,14 +502,7 @@ fn player_movement(
         Vec3::X
     };
 
-    let is_braking = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
-    movement.is_braking = is_braking;
-
     let mut input_direction = Vec3::ZERO;
-    let has_input = keyboard.pressed(KeyCode::KeyW)
-        || keyboard.pressed(KeyCode::KeyS)
-        || keyboard.pressed(KeyCode::KeyA)
-        || keyboard.pressed(KeyCode::KeyD);
 
     if keyboard.pressed(KeyCode::KeyW) {
         input_direction += forward_flat;
@@ -163,87 +517,455 @@ fn player_movement(
         input_direction += right_flat;
     }
 
-    if input_direction.length_squared() > 0.0001 {
-        input_direction = input_direction.normalize();
-    }
+    let mut input = MovementInput {
+        direction: input_direction,
+        brake: keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight),
+        handbrake: keyboard.pressed(KeyCode::KeyC),
+        jump: keyboard.just_pressed(KeyCode::Space),
+    };
 
-    let target_velocity = if is_braking {
-        Vec3::ZERO
-    } else if has_input {
-        input_direction * speed.current
-    } else {
-        Vec3::ZERO
+    let mut state = PlayerSimState {
+        velocity: movement.velocity,
+        vertical_velocity: velocity.linvel.y,
+        drift_factor: movement.drift_factor,
+        is_braking: movement.is_braking,
+        is_grounded: movement.is_grounded,
+        jumps_remaining: jump_state.jumps_remaining,
+        max_jumps: jump_state.max_jumps,
+        speed: speed.current,
+        max_speed: speed.max,
     };
 
-    let speed_ratio = speed.current / speed.max;
-    let drift_threshold = 0.4;
+    for step in substeps(time.delta_secs()) {
+        player_events.send_batch(simulate_player_step(&mut state, input, &tuning, &physics, step));
+        input.jump = false;
+    }
 
-    let acceleration_lerp = if is_braking {
-        let current_speed = movement.velocity.length();
-        let speed_normalized = (current_speed / speed.max).clamp(0.0, 1.0);
-        let brake_strength = 0.25 + speed_normalized * 0.15;
-        brake_strength
-    } else if has_input {
-        if speed_ratio > drift_threshold {
-            let drift = ((speed_ratio - drift_threshold) / (1.0 - drift_threshold)).powf(1.5);
-            movement.drift_factor = drift;
-            0.03 + drift * 0.07
-        } else {
-            movement.drift_factor = 0.0;
-            0.15
-        }
-    } else {
-        movement.drift_factor = 0.0;
-        0.08
-    };
+    movement.velocity = state.velocity;
+    movement.drift_factor = state.drift_factor;
+    movement.is_braking = state.is_braking;
+    jump_state.jumps_remaining = state.jumps_remaining;
+
+    velocity.linvel.x = state.velocity.x;
+    velocity.linvel.y = state.vertical_velocity;
+    velocity.linvel.z = state.velocity.z;
+}
 
-    movement.velocity = movement.velocity.lerp(target_velocity, acceleration_lerp);
+type WallRunBody = (
+    Entity,
+    &'static Transform,
+    &'static PlayerDimensions,
+    &'static mut PlayerMovement,
+    &'static mut JumpState,
+    &'static mut WallRun,
+    &'static mut GravityScale,
+    &'static mut Velocity,
+);
+
+fn update_wall_run(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut player_query: Query<WallRunBody, With<Player>>,
+    rapier_context: ReadRapierContext,
+    time: Res<Time>,
+    paused: Res<Paused>,
+) {
+    if paused.0 {
+        return;
+    }
 
-    velocity.linvel.x = movement.velocity.x;
-    velocity.linvel.z = movement.velocity.z;
+    let rapier_context = rapier_context.single();
 
-    let capsule_half_height = 0.5;
-    let capsule_radius = 0.3;
-    let ray_pos = transform.translation - Vec3::Y * capsule_half_height;
-    let ray_dir = Vec3::NEG_Y;
-    let max_toi = capsule_radius + 0.1;
+    let Ok((player_entity, transform, dimensions, mut movement, mut jump_state, mut wall_run, mut gravity, mut velocity)) = player_query.get_single_mut() else {
+        return;
+    };
+
+    for step in substeps(time.delta_secs()) {
+        wall_run.tick(step);
+    }
+
+    let holding_forward = keyboard.pressed(KeyCode::KeyW);
+    let horizontal_speed = Vec2::new(movement.velocity.x, movement.velocity.z).length();
     let filter = QueryFilter::default().exclude_rigid_body(player_entity);
+    let max_toi = dimensions.radius + 0.3;
+
+    let wall_hit = wall_probe_directions(movement.velocity).and_then(|directions| {
+        directions.into_iter().find_map(|direction| {
+            rapier_context
+                .cast_ray_and_get_normal(transform.translation, direction, max_toi, true, filter)
+                .map(|(_, hit)| hit.normal)
+                .filter(|normal| is_wall_normal(*normal))
+        })
+    });
+
+    if wall_run.active {
+        let jumped = keyboard.just_pressed(KeyCode::Space);
+        let ended = movement.is_grounded
+            || !holding_forward
+            || wall_run.timer <= 0.0
+            || wall_hit.is_none();
+
+        if jumped {
+            let kick = wall_run.wall_normal * WALL_KICK_SPEED;
+            movement.velocity += kick;
+            velocity.linvel.x += kick.x;
+            velocity.linvel.z += kick.z;
+        }
 
-    let is_grounded = rapier_context
-        .cast_ray(ray_pos, ray_dir, max_toi, true, filter)
-        .is_some();
+        if jumped || ended {
+            wall_run.detach();
+            gravity.0 = 1.0;
+        } else {
+            velocity.linvel.y = velocity.linvel.y.max(0.0);
+        }
+    } else if let Some(normal) = wall_hit.filter(|_| {
+        wall_run.can_attach()
+            && !movement.is_grounded
+            && holding_forward
+            && horizontal_speed >= WALL_RUN_MIN_SPEED
+    }) {
+        wall_run.attach(Vec3::new(normal.x, 0.0, normal.z).normalize_or_zero());
+        jump_state.jumps_remaining = jump_state.jumps_remaining.max(1);
+        gravity.0 = WALL_RUN_GRAVITY_SCALE;
+    }
+}
 
-    if is_grounded {
-        jump_state.jumps_remaining = jump_state.max_jumps - 1;
+fn update_stamina(
+    time: Res<Time>,
+    paused: Res<Paused>,
+    mut query: Query<(&mut Stamina, &PlayerMovement, &PlayerSpeed), With<Player>>,
+    mut player_events: EventWriter<PlayerEvent>,
+) {
+    if paused.0 {
+        return;
     }
 
-    if keyboard.just_pressed(KeyCode::Space) {
-        if is_grounded {
-            velocity.linvel.y = jump_force;
-            jump_state.jumps_remaining = jump_state.max_jumps - 1;
-        } else if jump_state.jumps_remaining > 0 {
-            velocity.linvel.y = double_jump_force;
-            jump_state.jumps_remaining -= 1;
+    let Ok((mut stamina, movement, speed)) = query.get_single_mut() else {
+        return;
+    };
+
+    let horizontal_speed = Vec2::new(movement.velocity.x, movement.velocity.z).length();
+    let is_sprinting = movement.is_grounded && horizontal_speed > speed.max * stamina.sprint_speed_ratio;
+
+    for step in substeps(time.delta_secs()) {
+        if let Some(event) = stamina.update(is_sprinting, step) {
+            player_events.send(event);
         }
     }
 }
 
 fn check_death(
     mut query: Query<(&mut Transform, &mut Velocity, &mut PlayerMovement, &mut JumpState, &SpawnPoint), With<Player>>,
+    world_rules: Res<WorldRules>,
+    spawn_points: Res<SpawnPoints>,
 ) {
     let Ok((mut transform, mut velocity, mut movement, mut jump_state, spawn_point)) = query.get_single_mut() else {
         return;
     };
 
-    let death_y = -20.0;
-
-    if transform.translation.y < death_y {
-        transform.translation = spawn_point.0;
+    if transform.translation.y < world_rules.death_y {
+        transform.translation = spawn_points.nearest(transform.translation).unwrap_or(spawn_point.0);
         velocity.linvel = Vec3::ZERO;
         velocity.angvel = Vec3::ZERO;
         movement.velocity = Vec3::ZERO;
         movement.drift_factor = 0.0;
         movement.is_braking = false;
+        movement.fall_speed = 0.0;
         jump_state.jumps_remaining = jump_state.max_jumps - 1;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use crate::camera::SpectatorMode;
+
+    fn grounded_state() -> PlayerSimState {
+        PlayerSimState {
+            velocity: Vec3::ZERO,
+            vertical_velocity: 0.0,
+            drift_factor: 0.0,
+            is_braking: false,
+            is_grounded: true,
+            jumps_remaining: 1,
+            max_jumps: 2,
+            speed: 8.0,
+            max_speed: 20.0,
+        }
+    }
+
+    #[test]
+    fn jump_then_land_emits_jumped_and_landed() {
+        let tuning = MovementTuning::default();
+        let physics = PhysicsTuning::default();
+        let mut state = grounded_state();
+        let jump = MovementInput { jump: true, ..default() };
+
+        let events = simulate_player_step(&mut state, jump, &tuning, &physics, 1.0 / 60.0);
+        assert_eq!(events, vec![PlayerEvent::Jumped { air_jump: false }]);
+        assert_eq!(state.vertical_velocity, physics.jump_force);
+
+        let (fall_speed, landed) = track_landing(0.0, true, false, physics.jump_force);
+        assert_eq!(landed, None);
+        let (fall_speed, landed) = track_landing(fall_speed, false, false, -9.0);
+        assert_eq!(landed, None);
+        let (fall_speed, landed) = track_landing(fall_speed, false, true, -2.0);
+        assert_eq!(landed, Some(PlayerEvent::Landed { impact_speed: 9.0 }));
+        assert_eq!(fall_speed, 0.0);
+    }
+
+    #[test]
+    fn air_jump_is_flagged() {
+        let mut state = PlayerSimState { is_grounded: false, ..grounded_state() };
+        let jump = MovementInput { jump: true, ..default() };
+
+        let events = simulate_player_step(&mut state, jump, &MovementTuning::default(), &PhysicsTuning::default(), 1.0 / 60.0);
+        assert_eq!(events, vec![PlayerEvent::Jumped { air_jump: true }]);
+        assert_eq!(state.jumps_remaining, 0);
+
+        let events = simulate_player_step(&mut state, jump, &MovementTuning::default(), &PhysicsTuning::default(), 1.0 / 60.0);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn landing_while_grounded_emits_nothing() {
+        assert_eq!(track_landing(0.0, true, true, 0.0), (0.0, None));
+    }
+
+    #[test]
+    fn sprint_start_and_stop_emit_once() {
+        let mut stamina = Stamina::default();
+
+        assert_eq!(stamina.update(true, 0.1), Some(PlayerEvent::SprintStarted));
+        assert_eq!(stamina.update(true, 0.1), None);
+        assert_eq!(stamina.update(false, 0.1), Some(PlayerEvent::SprintEnded));
+        assert_eq!(stamina.update(false, 0.1), None);
+    }
+
+    #[test]
+    fn drift_start_is_emitted_once() {
+        let tuning = MovementTuning::default();
+        let physics = PhysicsTuning::default();
+        let mut state = PlayerSimState { speed: 20.0, ..grounded_state() };
+        let input = MovementInput { direction: Vec3::X, ..default() };
+
+        let events = simulate_player_step(&mut state, input, &tuning, &physics, 1.0 / 60.0);
+        assert_eq!(events, vec![PlayerEvent::DriftStarted]);
+
+        let events = simulate_player_step(&mut state, input, &tuning, &physics, 1.0 / 60.0);
+        assert!(events.is_empty());
+    }
+
+    fn movement_world(paused: bool) -> World {
+        let mut world = World::new();
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        keyboard.press(KeyCode::KeyW);
+        let mut time = Time::<()>::default();
+        time.advance_by(std::time::Duration::from_millis(16));
+
+        world.insert_resource(keyboard);
+        world.insert_resource(time);
+        world.insert_resource(Paused(paused));
+        world.insert_resource(MovementTuning::default());
+        world.insert_resource(PhysicsTuning::default());
+        world.init_resource::<Events<PlayerEvent>>();
+        world.spawn((Camera3d::default(), Transform::default()));
+        world.spawn((
+            Player,
+            Velocity::zero(),
+            PlayerSpeed::default(),
+            PlayerMovement {
+                velocity: Vec3::ZERO,
+                drift_factor: 0.0,
+                is_braking: false,
+                is_grounded: true,
+                ground_surface: None,
+                fall_speed: 0.0,
+            },
+            JumpState {
+                jumps_remaining: 1,
+                max_jumps: 2,
+            },
+        ));
+        world
+    }
+
+    fn player_velocity(world: &mut World) -> Vec3 {
+        world.query_filtered::<&Velocity, With<Player>>().single(world).linvel
+    }
+
+    #[test]
+    fn movement_is_frozen_while_paused() {
+        let mut world = movement_world(true);
+        world.run_system_once(player_movement).unwrap();
+        assert_eq!(player_velocity(&mut world), Vec3::ZERO);
+
+        let mut world = movement_world(false);
+        world.run_system_once(player_movement).unwrap();
+        assert!(player_velocity(&mut world).z < 0.0);
+    }
+
+    #[test]
+    fn braking_decelerates_to_a_stop() {
+        let tuning = MovementTuning::default();
+        let physics = PhysicsTuning::default();
+        let mut state = PlayerSimState { velocity: Vec3::new(0.0, 0.0, -8.0), ..grounded_state() };
+        let brake = MovementInput { direction: Vec3::NEG_Z, brake: true, ..default() };
+
+        let mut previous = state.velocity.length();
+        for _ in 0..120 {
+            simulate_player_step(&mut state, brake, &tuning, &physics, 1.0 / 60.0);
+            let speed = state.velocity.length();
+            assert!(speed <= previous);
+            previous = speed;
+        }
+
+        assert!(state.is_braking);
+        assert!(previous < 0.01);
+    }
+
+    #[test]
+    fn drift_only_starts_above_the_threshold() {
+        let tuning = MovementTuning::default();
+        let physics = PhysicsTuning::default();
+        let input = MovementInput { direction: Vec3::X, ..default() };
+
+        let mut slow = PlayerSimState { speed: 20.0 * tuning.drift_threshold, ..grounded_state() };
+        simulate_player_step(&mut slow, input, &tuning, &physics, 1.0 / 60.0);
+        assert_eq!(slow.drift_factor, 0.0);
+
+        let mut fast = PlayerSimState { speed: 20.0, ..grounded_state() };
+        simulate_player_step(&mut fast, input, &tuning, &physics, 1.0 / 60.0);
+        assert!((fast.drift_factor - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn drift_threshold_is_tunable_and_handbrake_forces_drift() {
+        let physics = PhysicsTuning::default();
+        let input = MovementInput { direction: Vec3::X, ..default() };
+        let state = PlayerSimState { speed: 12.0, ..grounded_state() };
+
+        let mut loose = state;
+        simulate_player_step(&mut loose, input, &MovementTuning::default(), &physics, 1.0 / 60.0);
+        assert!(loose.drift_factor > 0.0);
+
+        let strict = MovementTuning { drift_threshold: 0.8, ..default() };
+        let mut grippy = state;
+        simulate_player_step(&mut grippy, input, &strict, &physics, 1.0 / 60.0);
+        assert_eq!(grippy.drift_factor, 0.0);
+
+        let mut handbrake = PlayerSimState { speed: 4.0, ..state };
+        let events = simulate_player_step(&mut handbrake, MovementInput { handbrake: true, ..input }, &strict, &physics, 1.0 / 60.0);
+        assert_eq!(handbrake.drift_factor, 1.0);
+        assert_eq!(events, vec![PlayerEvent::DriftStarted]);
+    }
+
+    #[test]
+    fn ground_ray_reaches_just_past_the_capsule_bottom() {
+        for dimensions in [PlayerDimensions::default(), PlayerDimensions { half_height: 0.9, radius: 0.45, eye_offset: 2.0 }] {
+            let position = Vec3::new(1.0, 5.0, -2.0);
+            let (origin, length) = dimensions.ground_ray(position);
+            let capsule_bottom = position.y - dimensions.half_height - dimensions.radius;
+
+            assert_eq!(origin.xz(), position.xz());
+            assert!(origin.y > capsule_bottom);
+            assert!((origin.y - length - (capsule_bottom - 0.1)).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn wall_probes_point_sideways_from_movement() {
+        let [right, left] = wall_probe_directions(Vec3::new(0.0, -3.0, -5.0)).unwrap();
+        assert!(right.abs_diff_eq(Vec3::X, 1e-5));
+        assert!(left.abs_diff_eq(Vec3::NEG_X, 1e-5));
+        assert_eq!(wall_probe_directions(Vec3::Y * 10.0), None);
+
+        assert!(is_wall_normal(Vec3::X));
+        assert!(!is_wall_normal(Vec3::Y));
+    }
+
+    #[test]
+    fn wall_run_cooldown_blocks_reattaching() {
+        let mut wall_run = WallRun::default();
+        assert!(wall_run.can_attach());
+
+        wall_run.attach(Vec3::X);
+        assert!(!wall_run.can_attach());
+        wall_run.tick(WALL_RUN_DURATION);
+        assert!(wall_run.timer <= 0.0);
+
+        wall_run.detach();
+        assert!(!wall_run.can_attach());
+        wall_run.tick(WALL_RUN_COOLDOWN / 2.0);
+        assert!(!wall_run.can_attach());
+        wall_run.tick(WALL_RUN_COOLDOWN);
+        assert!(wall_run.can_attach());
+    }
+
+    #[test]
+    fn spectating_suspends_player_movement() {
+        let mut schedule = Schedule::default();
+        schedule.add_systems(player_movement.run_if(not(spectating)));
+
+        let mut world = movement_world(false);
+        world.insert_resource(SpectatorMode { active: true, ..default() });
+        schedule.run(&mut world);
+        assert_eq!(player_velocity(&mut world), Vec3::ZERO);
+
+        world.resource_mut::<SpectatorMode>().active = false;
+        schedule.run(&mut world);
+        assert!(player_velocity(&mut world).z < 0.0);
+    }
+
+    #[test]
+    fn jumps_use_the_tuned_forces() {
+        let physics = PhysicsTuning { jump_force: 10.0, air_jump_force: 4.0, ..default() };
+        let jump = MovementInput { jump: true, ..default() };
+        let mut state = grounded_state();
+
+        simulate_player_step(&mut state, jump, &MovementTuning::default(), &physics, 1.0 / 60.0);
+        assert_eq!(state.vertical_velocity, 10.0);
+
+        state.is_grounded = false;
+        simulate_player_step(&mut state, jump, &MovementTuning::default(), &physics, 1.0 / 60.0);
+        assert_eq!(state.vertical_velocity, 4.0);
+    }
+
+    #[test]
+    fn substeps_split_hitches_and_cap_the_count() {
+        assert_eq!(substeps(1.0 / 60.0).collect::<Vec<_>>(), vec![1.0 / 60.0]);
+        assert_eq!(substeps(0.5).count(), 5);
+        assert!((substeps(0.5).sum::<f32>() - 0.5).abs() < 1e-6);
+        assert!(substeps(0.25).all(|step| step <= MAX_STEP_DT));
+        assert_eq!(substeps(10.0).count(), MAX_SUBSTEPS);
+        assert!((substeps(10.0).sum::<f32>() - MAX_STEP_DT * MAX_SUBSTEPS as f32).abs() < 1e-5);
+    }
+
+    #[test]
+    fn a_hitch_matches_the_same_time_in_small_frames() {
+        let run = |frames: usize, dt: f32| {
+            let mut world = movement_world(false);
+            let player = world.query_filtered::<Entity, With<Player>>().single(&world);
+            world.entity_mut(player).insert(Stamina::default());
+            world.get_mut::<PlayerMovement>(player).unwrap().velocity = Vec3::new(0.0, 0.0, -25.0);
+
+            for _ in 0..frames {
+                world.resource_mut::<Time>().advance_by(std::time::Duration::from_secs_f32(dt));
+                world.run_system_once(update_stamina).unwrap();
+            }
+            let stamina = world.get::<Stamina>(player).unwrap().current;
+
+            for _ in 0..frames {
+                world.resource_mut::<Time>().advance_by(std::time::Duration::from_secs_f32(dt));
+                world.run_system_once(player_movement).unwrap();
+            }
+            (stamina, player_velocity(&mut world))
+        };
+
+        let (hitch_stamina, hitch_velocity) = run(1, 0.5);
+        let (smooth_stamina, smooth_velocity) = run(50, 0.01);
+        assert!(hitch_stamina < Stamina::default().max);
+        assert!((hitch_stamina - smooth_stamina).abs() < 1e-3, "{hitch_stamina} vs {smooth_stamina}");
+        assert!(hitch_velocity.abs_diff_eq(smooth_velocity, 1e-3), "{hitch_velocity} vs {smooth_velocity}");
+    }
+}
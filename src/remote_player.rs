@@ -1,5 +1,7 @@
 use bevy::prelude::*;
-use crate::network::{NetworkEvent, PlayerRegistry};
+use crate::camera::FirstPersonCamera;
+use crate::menu::GameState;
+use crate::network::{now_millis, NetworkEvent, PlayerRegistry, INTERPOLATION_DELAY_MS};
 
 pub struct RemotePlayerPlugin;
 
@@ -8,14 +10,33 @@ impl Plugin for RemotePlayerPlugin {
         app.add_systems(Update, (
             spawn_remote_players,
             update_remote_players,
+            update_nameplates,
             despawn_remote_players,
-        ));
+        ))
+        .add_systems(OnExit(GameState::InGame), cleanup_remote_players);
     }
 }
 
 #[derive(Component)]
 pub struct RemotePlayer {
     pub id: u32,
+    pub previous_position: Vec3,
+    pub facing: Quat,
+}
+
+#[derive(Component)]
+pub struct Nameplate {
+    pub owner: Entity,
+}
+
+const NAMEPLATE_HEIGHT: f32 = 1.4;
+const NAMEPLATE_FADE_START: f32 = 15.0;
+const NAMEPLATE_FADE_END: f32 = 40.0;
+const PLAYER_HUE_STEP: f32 = 137.508;
+
+pub fn player_color(player_id: u32) -> Color {
+    let hue = (player_id as f32 * PLAYER_HUE_STEP).rem_euclid(360.0);
+    Color::hsl(hue, 0.6, 0.55)
 }
 
 fn spawn_remote_players(
@@ -33,15 +54,34 @@ fn spawn_remote_players(
                         let entity = commands.spawn((
                             Mesh3d(meshes.add(Capsule3d::new(0.4, 1.6))),
                             MeshMaterial3d(materials.add(StandardMaterial {
-                                base_color: Color::srgb(0.3, 0.5, 0.8),
+                                base_color: player_color(*id),
                                 ..default()
                             })),
                             Transform::from_translation(player_data.position)
                                 .with_rotation(player_data.rotation),
-                            RemotePlayer { id: *id },
+                            RemotePlayer {
+                                id: *id,
+                                previous_position: player_data.position,
+                                facing: player_data.rotation,
+                            },
                         )).id();
                         
                         player_data.entity = Some(entity);
+
+                        commands.spawn((
+                            Text::new(format!("Player {}", id)),
+                            TextFont {
+                                font_size: 18.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                            Node {
+                                position_type: PositionType::Absolute,
+                                ..default()
+                            },
+                            Visibility::Hidden,
+                            Nameplate { owner: entity },
+                        ));
                     }
                 }
             }
@@ -52,28 +92,191 @@ fn spawn_remote_players(
 
 fn update_remote_players(
     player_registry: Res<PlayerRegistry>,
-    mut query: Query<(&RemotePlayer, &mut Transform)>,
+    mut query: Query<(&mut RemotePlayer, &mut Transform)>,
 ) {
-    for (remote, mut transform) in query.iter_mut() {
+    let render_time = now_millis().saturating_sub(INTERPOLATION_DELAY_MS);
+
+    for (mut remote, mut transform) in query.iter_mut() {
         if let Some(player_data) = player_registry.players.get(&remote.id) {
-            transform.translation = transform.translation.lerp(player_data.position, 0.3);
-            transform.rotation = transform.rotation.slerp(player_data.rotation, 0.3);
+            if let Some(facing) = facing_from_movement(player_data.position - remote.previous_position) {
+                remote.facing = facing;
+            }
+            remote.previous_position = player_data.position;
+
+            let target_rotation = if player_data.rotation.abs_diff_eq(Quat::IDENTITY, 1e-4) {
+                remote.facing
+            } else {
+                player_data.rotation
+            };
+
+            let target_position = player_registry
+                .position_at(remote.id, render_time)
+                .unwrap_or(player_data.position);
+
+            transform.translation = transform.translation.lerp(target_position, 0.3);
+            transform.rotation = transform.rotation.slerp(target_rotation, 0.3);
         }
     }
 }
 
+pub fn facing_from_movement(delta: Vec3) -> Option<Quat> {
+    let horizontal = Vec2::new(delta.x, delta.z);
+    if horizontal.length_squared() < 1e-6 {
+        return None;
+    }
+
+    Some(Quat::from_rotation_y((-horizontal.x).atan2(-horizontal.y)))
+}
+
+fn update_nameplates(
+    camera_query: Query<(&Camera, &GlobalTransform), With<FirstPersonCamera>>,
+    player_query: Query<&GlobalTransform, With<RemotePlayer>>,
+    mut nameplate_query: Query<(&Nameplate, &mut Node, &mut TextColor, &mut Visibility)>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+    let Some(viewport_size) = camera.logical_viewport_size() else {
+        return;
+    };
+
+    for (nameplate, mut node, mut color, mut visibility) in nameplate_query.iter_mut() {
+        let Ok(player_transform) = player_query.get(nameplate.owner) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        let head_position = player_transform.translation() + Vec3::Y * NAMEPLATE_HEIGHT;
+        let distance = camera_transform.translation().distance(head_position);
+        let alpha = nameplate_alpha(distance);
+
+        match project_to_screen(camera.clip_from_view(), camera_transform, viewport_size, head_position) {
+            Some(screen_position) if alpha > 0.0 => {
+                node.left = Val::Px(screen_position.x);
+                node.top = Val::Px(screen_position.y);
+                color.0 = Color::srgba(1.0, 1.0, 1.0, alpha);
+                *visibility = Visibility::Visible;
+            }
+            _ => {
+                *visibility = Visibility::Hidden;
+            }
+        }
+    }
+}
+
+pub fn project_to_screen(clip_from_view: Mat4, camera_transform: &GlobalTransform, viewport_size: Vec2, world_position: Vec3) -> Option<Vec2> {
+    let clip_from_world = clip_from_view * camera_transform.compute_matrix().inverse();
+    let ndc = clip_from_world.project_point3(world_position);
+    if !ndc.is_finite() || !(0.0..=1.0).contains(&ndc.z) {
+        return None;
+    }
+
+    let screen = (ndc.truncate() + Vec2::ONE) / 2.0 * viewport_size;
+    Some(Vec2::new(screen.x, viewport_size.y - screen.y))
+}
+
+pub fn nameplate_alpha(distance: f32) -> f32 {
+    if distance <= NAMEPLATE_FADE_START {
+        1.0
+    } else {
+        (1.0 - (distance - NAMEPLATE_FADE_START) / (NAMEPLATE_FADE_END - NAMEPLATE_FADE_START)).max(0.0)
+    }
+}
+
 fn despawn_remote_players(
     mut commands: Commands,
     mut events: EventReader<NetworkEvent>,
     query: Query<(Entity, &RemotePlayer)>,
+    nameplate_query: Query<(Entity, &Nameplate)>,
 ) {
     for event in events.read() {
         if let NetworkEvent::PlayerLeft(id) = event {
             for (entity, remote) in query.iter() {
                 if remote.id == *id {
                     commands.entity(entity).despawn_recursive();
+
+                    for (nameplate_entity, nameplate) in nameplate_query.iter() {
+                        if nameplate.owner == entity {
+                            commands.entity(nameplate_entity).despawn_recursive();
+                        }
+                    }
                 }
             }
         }
     }
 }
+
+type RemoteEntity = Or<(With<RemotePlayer>, With<Nameplate>)>;
+
+fn cleanup_remote_players(
+    mut commands: Commands,
+    mut player_registry: ResMut<PlayerRegistry>,
+    query: Query<Entity, RemoteEntity>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    for player in player_registry.players.values_mut() {
+        player.entity = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nameplates_fade_out_with_distance() {
+        assert_eq!(nameplate_alpha(0.0), 1.0);
+        assert_eq!(nameplate_alpha(NAMEPLATE_FADE_START), 1.0);
+
+        let halfway = (NAMEPLATE_FADE_START + NAMEPLATE_FADE_END) / 2.0;
+        assert!((nameplate_alpha(halfway) - 0.5).abs() < 1e-6);
+        assert_eq!(nameplate_alpha(NAMEPLATE_FADE_END), 0.0);
+        assert_eq!(nameplate_alpha(NAMEPLATE_FADE_END * 2.0), 0.0);
+    }
+
+    #[test]
+    fn facing_follows_horizontal_movement() {
+        let facing = facing_from_movement(Vec3::X).unwrap();
+        assert!((facing * Vec3::NEG_Z).abs_diff_eq(Vec3::X, 1e-5));
+
+        let facing = facing_from_movement(Vec3::new(0.0, 3.0, -2.0)).unwrap();
+        assert!((facing * Vec3::NEG_Z).abs_diff_eq(Vec3::NEG_Z, 1e-5));
+
+        assert_eq!(facing_from_movement(Vec3::Y), None);
+        assert_eq!(facing_from_movement(Vec3::ZERO), None);
+    }
+
+    #[test]
+    fn player_colors_are_stable_and_distinct() {
+        assert_eq!(player_color(3), player_color(3));
+
+        let hues: Vec<f32> = (1..=8).map(|id| Hsla::from(player_color(id)).hue).collect();
+        for (i, a) in hues.iter().enumerate() {
+            for b in &hues[i + 1..] {
+                let gap = (a - b).abs().min(360.0 - (a - b).abs());
+                assert!(gap > 20.0, "{a} and {b} are too close");
+            }
+        }
+    }
+
+    #[test]
+    fn nameplates_project_in_front_of_the_camera_only() {
+        use bevy::render::camera::CameraProjection;
+
+        let clip_from_view = PerspectiveProjection::default().get_clip_from_view();
+        let camera = GlobalTransform::from(Transform::from_xyz(0.0, 2.0, 0.0));
+        let viewport = Vec2::new(800.0, 600.0);
+
+        let center = project_to_screen(clip_from_view, &camera, viewport, Vec3::new(0.0, 2.0, -10.0)).unwrap();
+        assert!(center.abs_diff_eq(Vec2::new(400.0, 300.0), 1e-3));
+
+        let upper_right = project_to_screen(clip_from_view, &camera, viewport, Vec3::new(1.0, 3.0, -10.0)).unwrap();
+        assert!(upper_right.x > 400.0 && upper_right.y < 300.0);
+
+        assert_eq!(project_to_screen(clip_from_view, &camera, viewport, Vec3::new(0.0, 2.0, 10.0)), None);
+        assert_eq!(project_to_screen(clip_from_view, &camera, viewport, Vec3::new(1.0, 3.0, 0.5)), None);
+    }
+}
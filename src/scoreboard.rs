@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+use crate::menu::GameState;
+use crate::network::{NetworkMode, NetworkState, PlayerRegistry};
+use crate::player::Player;
+
+pub struct ScoreboardPlugin;
+
+impl Plugin for ScoreboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (
+            toggle_scoreboard,
+            update_scoreboard,
+        ).chain().run_if(in_state(GameState::InGame)))
+            .add_systems(OnExit(GameState::InGame), cleanup_scoreboard);
+    }
+}
+
+#[derive(Component)]
+struct ScoreboardUI;
+
+#[derive(Component)]
+struct ScoreboardText;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreboardRow {
+    pub id: u32,
+    pub position: Vec3,
+    pub is_local: bool,
+}
+
+pub fn scoreboard_rows(registry: &PlayerRegistry, local_id: u32, local_position: Option<Vec3>) -> Vec<ScoreboardRow> {
+    let mut rows: Vec<ScoreboardRow> = registry.players.values()
+        .filter(|player| player.id != local_id)
+        .map(|player| ScoreboardRow {
+            id: player.id,
+            position: player.position,
+            is_local: false,
+        })
+        .collect();
+
+    if let Some(position) = local_position {
+        rows.push(ScoreboardRow {
+            id: local_id,
+            position,
+            is_local: true,
+        });
+    }
+
+    rows.sort_by_key(|row| row.id);
+    rows
+}
+
+fn format_row(row: &ScoreboardRow) -> String {
+    let name = if row.is_local {
+        format!("Player {} (you)", row.id)
+    } else {
+        format!("Player {}", row.id)
+    };
+
+    format!(
+        "{:<18} {:>7.1} {:>7.1} {:>7.1}",
+        name, row.position.x, row.position.y, row.position.z
+    )
+}
+
+fn toggle_scoreboard(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    scoreboard_query: Query<Entity, With<ScoreboardUI>>,
+) {
+    if keyboard.just_pressed(KeyCode::Tab) && scoreboard_query.is_empty() {
+        commands
+            .spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    top: Val::Px(80.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ScoreboardUI,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Node {
+                        padding: UiRect::all(Val::Px(20.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.85)),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        ScoreboardText,
+                    ));
+                });
+            });
+    }
+
+    if !keyboard.pressed(KeyCode::Tab) {
+        for entity in &scoreboard_query {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn update_scoreboard(
+    net_state: Res<NetworkState>,
+    player_registry: Res<PlayerRegistry>,
+    player_query: Query<&Transform, With<Player>>,
+    mut text_query: Query<&mut Text, With<ScoreboardText>>,
+) {
+    let Ok(mut text) = text_query.get_single_mut() else {
+        return;
+    };
+
+    let local_position = player_query.get_single().ok().map(|transform| transform.translation);
+    let rows = scoreboard_rows(&player_registry, net_state.local_player_id, local_position);
+
+    let mut content = format!("PLAYERS ({})\n", rows.len());
+    if net_state.mode == NetworkMode::Client {
+        content.push_str(&format!("Ping: {:.0}ms\n", net_state.ping_ms));
+    }
+    content.push('\n');
+
+    for row in &rows {
+        content.push_str(&format_row(row));
+        content.push('\n');
+    }
+
+    **text = content;
+}
+
+fn cleanup_scoreboard(
+    mut commands: Commands,
+    scoreboard_query: Query<Entity, With<ScoreboardUI>>,
+) {
+    for entity in &scoreboard_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_list_every_player_once_in_id_order() {
+        let mut registry = PlayerRegistry::default();
+        registry.apply_update(3, Vec3::new(1.0, 2.0, 3.0), Quat::IDENTITY, 0);
+        registry.apply_update(1, Vec3::ZERO, Quat::IDENTITY, 0);
+        registry.apply_update(2, Vec3::X, Quat::IDENTITY, 0);
+
+        let rows = scoreboard_rows(&registry, 2, Some(Vec3::Y));
+        let ids: Vec<u32> = rows.iter().map(|row| row.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert_eq!(rows[1], ScoreboardRow { id: 2, position: Vec3::Y, is_local: true });
+        assert_eq!(rows[2].position, Vec3::new(1.0, 2.0, 3.0));
+        assert!(!rows[0].is_local && !rows[2].is_local);
+    }
+
+    #[test]
+    fn rows_without_local_player_only_show_remotes() {
+        let mut registry = PlayerRegistry::default();
+        registry.apply_update(5, Vec3::ZERO, Quat::IDENTITY, 0);
+
+        let rows = scoreboard_rows(&registry, 0, None);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(format_row(&rows[0]).split_whitespace().take(2).collect::<Vec<_>>(), ["Player", "5"]);
+    }
+}
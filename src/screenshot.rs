@@ -0,0 +1,130 @@
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, ScreenshotCaptured};
+use bevy::tasks::IoTaskPool;
+use std::path::{Path, PathBuf};
+use crate::network::now_millis;
+
+pub struct ScreenshotPlugin;
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HiddenHud>()
+            .add_systems(Update, (restore_hud, take_screenshot).chain());
+    }
+}
+
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+type HudRoot = (With<Node>, Without<Parent>);
+
+#[derive(Resource, Default)]
+struct HiddenHud(Vec<(Entity, Visibility)>);
+
+pub fn screenshot_path(dir: &Path, timestamp: u128, exists: impl Fn(&Path) -> bool) -> PathBuf {
+    let mut path = dir.join(format!("lspire-{timestamp}.png"));
+    let mut suffix = 1;
+
+    while exists(&path) {
+        path = dir.join(format!("lspire-{timestamp}-{suffix}.png"));
+        suffix += 1;
+    }
+
+    path
+}
+
+fn take_screenshot(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut hidden_hud: ResMut<HiddenHud>,
+    mut hud_query: Query<(Entity, &mut Visibility), HudRoot>,
+) {
+    if !keyboard.just_pressed(KeyCode::F12) {
+        return;
+    }
+
+    let clean = keyboard.pressed(KeyCode::ShiftLeft) || keyboard.pressed(KeyCode::ShiftRight);
+    if clean {
+        for (entity, mut visibility) in hud_query.iter_mut() {
+            hidden_hud.0.push((entity, *visibility));
+            *visibility = Visibility::Hidden;
+        }
+    }
+
+    let path = screenshot_path(Path::new(SCREENSHOT_DIR), now_millis(), |path| path.exists());
+    commands
+        .spawn(Screenshot::primary_window())
+        .observe(save_in_background(path));
+}
+
+fn save_in_background(path: PathBuf) -> impl FnMut(Trigger<ScreenshotCaptured>) {
+    move |mut trigger| {
+        let image = std::mem::take(&mut trigger.event_mut().0);
+        let path = path.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                match write_screenshot(image, &path) {
+                    Ok(()) => info!("Screenshot saved to {}", path.display()),
+                    Err(err) => error!("Cannot save screenshot: {err}"),
+                }
+            })
+            .detach();
+    }
+}
+
+fn write_screenshot(image: Image, path: &Path) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+    }
+
+    let image = image.try_into_dynamic().map_err(|err| err.to_string())?;
+    image.to_rgb8().save(path).map_err(|err| err.to_string())
+}
+
+fn restore_hud(
+    mut hidden_hud: ResMut<HiddenHud>,
+    mut visibility_query: Query<&mut Visibility>,
+) {
+    for (entity, previous) in hidden_hud.0.drain(..) {
+        if let Ok(mut visibility) = visibility_query.get_mut(entity) {
+            *visibility = previous;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use std::collections::HashSet;
+
+    #[test]
+    fn screenshot_paths_never_overwrite_existing_files() {
+        let dir = Path::new(SCREENSHOT_DIR);
+        assert_eq!(screenshot_path(dir, 42, |_| false), dir.join("lspire-42.png"));
+
+        let taken: HashSet<PathBuf> = [dir.join("lspire-42.png"), dir.join("lspire-42-1.png")].into_iter().collect();
+        assert_eq!(screenshot_path(dir, 42, |path| taken.contains(path)), dir.join("lspire-42-2.png"));
+    }
+
+    #[test]
+    fn clean_screenshots_hide_hud_roots_until_the_next_frame() {
+        let mut world = World::new();
+        let mut keyboard = ButtonInput::<KeyCode>::default();
+        keyboard.press(KeyCode::ShiftLeft);
+        keyboard.press(KeyCode::F12);
+        world.insert_resource(keyboard);
+        world.init_resource::<HiddenHud>();
+
+        let root = world.spawn((Node::default(), Visibility::Visible)).id();
+        let child = world.spawn((Node::default(), Visibility::Inherited)).set_parent(root).id();
+
+        world.run_system_once(take_screenshot).unwrap();
+        assert_eq!(world.get::<Visibility>(root), Some(&Visibility::Hidden));
+        assert_eq!(world.get::<Visibility>(child), Some(&Visibility::Inherited));
+        assert_eq!(world.query::<&Screenshot>().iter(&world).count(), 1);
+
+        world.run_system_once(restore_hud).unwrap();
+        assert_eq!(world.get::<Visibility>(root), Some(&Visibility::Visible));
+        assert!(world.resource::<HiddenHud>().0.is_empty());
+    }
+}
@@ -0,0 +1,599 @@
+use bevy::prelude::*;
+use bevy::window::CursorGrabMode;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use crate::audio::AudioSettings;
+use crate::camera::FirstPersonCamera;
+use crate::menu::GameState;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(settings_path().map(|path| load_settings(&path)).unwrap_or_default())
+            .init_resource::<AccessibilitySettings>()
+            .init_resource::<UiTheme>()
+            .add_systems(OnEnter(GameState::Settings), setup_settings_menu)
+            .add_systems(Update, settings_button_system.run_if(in_state(GameState::Settings)))
+            .add_systems(Update, restyle_buttons)
+            .add_systems(Update, (
+                settings_action,
+                update_settings_values,
+                apply_settings,
+                save_settings,
+            ).chain())
+            .add_systems(OnExit(GameState::Settings), cleanup_settings_menu);
+    }
+}
+
+pub const MIN_SENSITIVITY: f32 = 0.0005;
+pub const MAX_SENSITIVITY: f32 = 0.01;
+pub const MIN_FOV: f32 = 30.0;
+pub const MAX_FOV: f32 = 110.0;
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub sensitivity: f32,
+    pub fov: f32,
+    pub master_volume: f32,
+    pub raw_mouse_input: bool,
+    pub reduce_motion: bool,
+    pub disable_head_bob: bool,
+    pub disable_shake: bool,
+    pub disable_fov_kick: bool,
+    pub disable_drift_smoothing: bool,
+    pub high_contrast: bool,
+}
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct UiTheme {
+    pub high_contrast: bool,
+}
+
+pub const BUTTON_BORDER_WIDTH: f32 = 3.0;
+
+pub type ThemedButton = (&'static Interaction, &'static mut BackgroundColor, &'static mut BorderColor);
+pub type ChangedButton = (Changed<Interaction>, With<Button>);
+
+impl UiTheme {
+    pub fn button_color(&self, interaction: Interaction) -> Color {
+        match (interaction, self.high_contrast) {
+            (Interaction::Pressed, false) => Color::srgba(0.35, 0.75, 0.35, 0.95),
+            (Interaction::Hovered, false) => Color::srgba(0.25, 0.25, 0.25, 0.95),
+            (Interaction::None, false) => NORMAL_BUTTON,
+            (_, true) => Color::BLACK,
+        }
+    }
+
+    pub fn border_color(&self, interaction: Interaction) -> Color {
+        match (interaction, self.high_contrast) {
+            (_, false) => Color::NONE,
+            (Interaction::Pressed, true) => Color::srgb(0.2, 1.0, 0.2),
+            (Interaction::Hovered, true) => Color::srgb(1.0, 0.85, 0.0),
+            (Interaction::None, true) => Color::WHITE,
+        }
+    }
+
+    pub fn style_button(&self, interaction: Interaction, background: &mut BackgroundColor, border: &mut BorderColor) {
+        let fill = self.button_color(interaction);
+        if background.0 != fill {
+            background.0 = fill;
+        }
+
+        let outline = self.border_color(interaction);
+        if border.0 != outline {
+            border.0 = outline;
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct AccessibilitySettings {
+    pub reduce_motion: bool,
+    pub disable_head_bob: bool,
+    pub disable_shake: bool,
+    pub disable_fov_kick: bool,
+    pub disable_drift_smoothing: bool,
+}
+
+impl AccessibilitySettings {
+    pub fn head_bob(&self) -> bool {
+        !self.reduce_motion && !self.disable_head_bob
+    }
+
+    pub fn shake(&self) -> bool {
+        !self.reduce_motion && !self.disable_shake
+    }
+
+    pub fn fov_kick(&self) -> bool {
+        !self.reduce_motion && !self.disable_fov_kick
+    }
+
+    pub fn drift_smoothing(&self) -> bool {
+        !self.reduce_motion && !self.disable_drift_smoothing
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            sensitivity: 0.002,
+            fov: 45.0,
+            master_volume: 1.0,
+            raw_mouse_input: false,
+            reduce_motion: false,
+            disable_head_bob: false,
+            disable_shake: false,
+            disable_fov_kick: false,
+            disable_drift_smoothing: false,
+            high_contrast: false,
+        }
+    }
+}
+
+impl Settings {
+    pub fn set_sensitivity(&mut self, value: f32) {
+        self.sensitivity = value.clamp(MIN_SENSITIVITY, MAX_SENSITIVITY);
+    }
+
+    pub fn set_fov(&mut self, value: f32) {
+        self.fov = value.clamp(MIN_FOV, MAX_FOV);
+    }
+
+    pub fn set_master_volume(&mut self, value: f32) {
+        self.master_volume = value.clamp(0.0, 1.0);
+    }
+
+    pub fn sanitized(mut self) -> Self {
+        let defaults = Settings::default();
+        let finite_or = |value: f32, default: f32| if value.is_finite() { value } else { default };
+
+        self.set_sensitivity(finite_or(self.sensitivity, defaults.sensitivity));
+        self.set_fov(finite_or(self.fov, defaults.fov));
+        self.set_master_volume(finite_or(self.master_volume, defaults.master_volume));
+        self
+    }
+}
+
+pub fn settings_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_dir.join("lspire").join("settings.ron"))
+}
+
+pub fn load_settings(path: &Path) -> Settings {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|data| ron::from_str::<Settings>(&data).ok())
+        .map(Settings::sanitized)
+        .unwrap_or_default()
+}
+
+pub fn store_settings(path: &Path, settings: &Settings) -> Result<(), std::io::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let data = ron::ser::to_string_pretty(settings, ron::ser::PrettyConfig::default()).map_err(std::io::Error::other)?;
+    std::fs::write(path, data)
+}
+
+#[derive(Component)]
+struct SettingsUI;
+
+#[derive(Component, Clone, Copy)]
+enum SettingKind {
+    Sensitivity,
+    Fov,
+    Volume,
+    RawInput,
+    ReduceMotion,
+    HeadBob,
+    Shake,
+    FovKick,
+    DriftSmoothing,
+    HighContrast,
+}
+
+#[derive(Component)]
+enum SettingsButton {
+    Decrease(SettingKind),
+    Increase(SettingKind),
+    Back,
+}
+
+#[derive(Component)]
+struct SettingValueText(SettingKind);
+
+const NORMAL_BUTTON: Color = Color::srgba(0.15, 0.15, 0.15, 0.9);
+
+fn setup_settings_menu(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut windows: Query<&mut Window>,
+) {
+    for mut window in windows.iter_mut() {
+        window.cursor_options.grab_mode = CursorGrabMode::None;
+        window.cursor_options.visible = true;
+    }
+
+    commands.spawn((
+        Camera2d,
+        SettingsUI,
+    ));
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.1, 0.95)),
+            SettingsUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("SETTINGS"),
+                TextFont {
+                    font_size: 60.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    margin: UiRect::all(Val::Px(30.0)),
+                    ..default()
+                },
+            ));
+
+            spawn_setting_rows(parent, &settings);
+
+            spawn_button(parent, "Back", 250.0, SettingsButton::Back);
+        });
+}
+
+pub fn spawn_setting_rows(parent: &mut ChildBuilder, settings: &Settings) {
+    parent
+        .spawn(Node {
+            display: Display::Grid,
+            grid_template_columns: RepeatedGridTrack::auto(2),
+            column_gap: Val::Px(40.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            spawn_setting_row(parent, "Sensitivity", SettingKind::Sensitivity, settings);
+            spawn_setting_row(parent, "FOV", SettingKind::Fov, settings);
+            spawn_setting_row(parent, "Volume", SettingKind::Volume, settings);
+            spawn_setting_row(parent, "Raw Mouse", SettingKind::RawInput, settings);
+            spawn_setting_row(parent, "Reduce Motion", SettingKind::ReduceMotion, settings);
+            spawn_setting_row(parent, "Head Bob", SettingKind::HeadBob, settings);
+            spawn_setting_row(parent, "Screen Shake", SettingKind::Shake, settings);
+            spawn_setting_row(parent, "FOV Kick", SettingKind::FovKick, settings);
+            spawn_setting_row(parent, "Drift Smoothing", SettingKind::DriftSmoothing, settings);
+            spawn_setting_row(parent, "High Contrast", SettingKind::HighContrast, settings);
+        });
+}
+
+fn spawn_setting_row(parent: &mut ChildBuilder, label: &str, kind: SettingKind, settings: &Settings) {
+    parent
+        .spawn(Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(20.0),
+            margin: UiRect::all(Val::Px(10.0)),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    width: Val::Px(220.0),
+                    ..default()
+                },
+            ));
+
+            spawn_button(parent, "-", 60.0, SettingsButton::Decrease(kind));
+
+            parent.spawn((
+                Text::new(format_setting(kind, settings)),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+                Node {
+                    width: Val::Px(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                SettingValueText(kind),
+            ));
+
+            spawn_button(parent, "+", 60.0, SettingsButton::Increase(kind));
+        });
+}
+
+fn spawn_button(parent: &mut ChildBuilder, text: &str, width: f32, button_type: SettingsButton) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(width),
+                height: Val::Px(60.0),
+                margin: UiRect::all(Val::Px(10.0)),
+                border: UiRect::all(Val::Px(BUTTON_BORDER_WIDTH)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(NORMAL_BUTTON),
+            BorderColor(Color::NONE),
+            button_type,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(text),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn format_setting(kind: SettingKind, settings: &Settings) -> String {
+    match kind {
+        SettingKind::Sensitivity => format!("{:.1}", settings.sensitivity * 1000.0),
+        SettingKind::Fov => format!("{:.0}", settings.fov),
+        SettingKind::Volume => format!("{:.0}%", settings.master_volume * 100.0),
+        SettingKind::RawInput => if settings.raw_mouse_input { "On" } else { "Off" }.to_string(),
+        SettingKind::ReduceMotion => if settings.reduce_motion { "On" } else { "Off" }.to_string(),
+        SettingKind::HeadBob => if settings.disable_head_bob { "Off" } else { "On" }.to_string(),
+        SettingKind::Shake => if settings.disable_shake { "Off" } else { "On" }.to_string(),
+        SettingKind::FovKick => if settings.disable_fov_kick { "Off" } else { "On" }.to_string(),
+        SettingKind::DriftSmoothing => if settings.disable_drift_smoothing { "Off" } else { "On" }.to_string(),
+        SettingKind::HighContrast => if settings.high_contrast { "On" } else { "Off" }.to_string(),
+    }
+}
+
+fn step_setting(settings: &mut Settings, kind: SettingKind, direction: f32) {
+    match kind {
+        SettingKind::Sensitivity => settings.set_sensitivity(settings.sensitivity + direction * 0.0005),
+        SettingKind::Fov => settings.set_fov(settings.fov + direction * 5.0),
+        SettingKind::Volume => settings.set_master_volume(settings.master_volume + direction * 0.1),
+        SettingKind::RawInput => settings.raw_mouse_input = direction > 0.0,
+        SettingKind::ReduceMotion => settings.reduce_motion = direction > 0.0,
+        SettingKind::HeadBob => settings.disable_head_bob = direction < 0.0,
+        SettingKind::Shake => settings.disable_shake = direction < 0.0,
+        SettingKind::FovKick => settings.disable_fov_kick = direction < 0.0,
+        SettingKind::DriftSmoothing => settings.disable_drift_smoothing = direction < 0.0,
+        SettingKind::HighContrast => settings.high_contrast = direction > 0.0,
+    }
+}
+
+fn settings_button_system(
+    theme: Res<UiTheme>,
+    mut interaction_query: Query<ThemedButton, ChangedButton>,
+) {
+    for (interaction, mut color, mut border) in &mut interaction_query {
+        theme.style_button(*interaction, &mut color, &mut border);
+    }
+}
+
+fn restyle_buttons(
+    theme: Res<UiTheme>,
+    mut button_query: Query<(Ref<Button>, ThemedButton)>,
+) {
+    for (button, (interaction, mut color, mut border)) in button_query.iter_mut() {
+        if theme.is_changed() || button.is_added() {
+            theme.style_button(*interaction, &mut color, &mut border);
+        }
+    }
+}
+
+fn settings_action(
+    interaction_query: Query<(&Interaction, &SettingsButton), ChangedButton>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match button {
+                SettingsButton::Decrease(kind) => step_setting(&mut settings, *kind, -1.0),
+                SettingsButton::Increase(kind) => step_setting(&mut settings, *kind, 1.0),
+                SettingsButton::Back => {
+                    next_state.set(GameState::Menu);
+                }
+            }
+        }
+    }
+}
+
+fn update_settings_values(
+    settings: Res<Settings>,
+    mut text_query: Query<(&mut Text, &SettingValueText)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (mut text, value) in text_query.iter_mut() {
+        **text = format_setting(value.0, &settings);
+    }
+}
+
+fn apply_settings(
+    settings: Res<Settings>,
+    mut audio_settings: ResMut<AudioSettings>,
+    mut accessibility: ResMut<AccessibilitySettings>,
+    mut theme: ResMut<UiTheme>,
+    mut camera_query: Query<&mut FirstPersonCamera>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    audio_settings.master = settings.master_volume;
+    accessibility.reduce_motion = settings.reduce_motion;
+    accessibility.disable_head_bob = settings.disable_head_bob;
+    accessibility.disable_shake = settings.disable_shake;
+    accessibility.disable_fov_kick = settings.disable_fov_kick;
+    accessibility.disable_drift_smoothing = settings.disable_drift_smoothing;
+    theme.high_contrast = settings.high_contrast;
+
+    for mut fps_camera in camera_query.iter_mut() {
+        fps_camera.sensitivity = settings.sensitivity;
+        fps_camera.raw_input = settings.raw_mouse_input;
+        fps_camera.base_fov = settings.fov;
+    }
+}
+
+fn save_settings(settings: Res<Settings>) {
+    if !settings.is_changed() || settings.is_added() {
+        return;
+    }
+
+    let Some(path) = settings_path() else {
+        return;
+    };
+
+    if let Err(error) = store_settings(&path, &settings) {
+        warn!("Could not save settings to {}: {}", path.display(), error);
+    }
+}
+
+fn cleanup_settings_menu(
+    mut commands: Commands,
+    settings_query: Query<Entity, With<SettingsUI>>,
+) {
+    for entity in &settings_query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERACTIONS: [Interaction; 3] = [Interaction::None, Interaction::Hovered, Interaction::Pressed];
+
+    fn contrast(a: Color, b: Color) -> f32 {
+        let luminance = |color: Color| {
+            let linear = color.to_linear();
+            0.2126 * linear.red + 0.7152 * linear.green + 0.0722 * linear.blue
+        };
+        let (high, low) = (luminance(a).max(luminance(b)), luminance(a).min(luminance(b)));
+        (high + 0.05) / (low + 0.05)
+    }
+
+    #[test]
+    fn high_contrast_text_meets_aaa() {
+        let theme = UiTheme { high_contrast: true };
+        for interaction in INTERACTIONS {
+            assert!(contrast(Color::WHITE, theme.button_color(interaction)) >= 7.0);
+        }
+    }
+
+    #[test]
+    fn high_contrast_outlines_stand_out_and_differ() {
+        let theme = UiTheme { high_contrast: true };
+        for interaction in INTERACTIONS {
+            assert!(contrast(theme.border_color(interaction), theme.button_color(interaction)) >= 3.0);
+        }
+        assert_ne!(theme.border_color(Interaction::Hovered), theme.border_color(Interaction::Pressed));
+        assert_ne!(theme.border_color(Interaction::None), theme.border_color(Interaction::Hovered));
+    }
+
+    fn temp_settings_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("lspire-settings-{}-{name}", std::process::id())).join("settings.ron")
+    }
+
+    #[test]
+    fn missing_file_loads_defaults() {
+        assert_eq!(load_settings(&temp_settings_path("missing")), Settings::default());
+    }
+
+    #[test]
+    fn save_load_round_trip() {
+        let path = temp_settings_path("round-trip");
+        let settings = Settings {
+            sensitivity: 0.004,
+            fov: 90.0,
+            master_volume: 0.5,
+            raw_mouse_input: true,
+            disable_shake: true,
+            disable_drift_smoothing: true,
+            high_contrast: true,
+            ..default()
+        };
+
+        store_settings(&path, &settings).unwrap();
+        assert_eq!(load_settings(&path), settings);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn missing_fields_keep_the_rest_of_the_file() {
+        let settings: Settings = ron::from_str("(fov: 80.0, disable_head_bob: true)").unwrap();
+        assert_eq!(settings, Settings { fov: 80.0, disable_head_bob: true, ..default() });
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped_on_load() {
+        let path = temp_settings_path("clamped");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, "(fov: 500.0, master_volume: -1.0)").unwrap();
+
+        let settings = load_settings(&path);
+        assert_eq!(settings.fov, MAX_FOV);
+        assert_eq!(settings.master_volume, 0.0);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn stepping_settings_stays_in_range() {
+        let mut settings = Settings::default();
+        for _ in 0..100 {
+            step_setting(&mut settings, SettingKind::Sensitivity, -1.0);
+            step_setting(&mut settings, SettingKind::Fov, -1.0);
+        }
+        assert_eq!(settings.sensitivity, MIN_SENSITIVITY);
+        assert!(settings.sensitivity > 0.0);
+        assert_eq!(settings.fov, MIN_FOV);
+
+        for _ in 0..100 {
+            step_setting(&mut settings, SettingKind::Sensitivity, 1.0);
+            step_setting(&mut settings, SettingKind::Fov, 1.0);
+        }
+        assert_eq!(settings.sensitivity, MAX_SENSITIVITY);
+        assert_eq!(settings.fov, MAX_FOV);
+    }
+
+    #[test]
+    fn non_finite_values_fall_back_to_defaults() {
+        let settings = Settings { sensitivity: f32::NAN, fov: f32::INFINITY, ..default() }.sanitized();
+        assert_eq!(settings.sensitivity, Settings::default().sensitivity);
+        assert_eq!(settings.fov, Settings::default().fov);
+    }
+
+    #[test]
+    fn default_theme_has_no_outline() {
+        let theme = UiTheme::default();
+        for interaction in INTERACTIONS {
+            assert_eq!(theme.border_color(interaction), Color::NONE);
+        }
+    }
+}
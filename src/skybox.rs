@@ -1,23 +1,79 @@
 use bevy::prelude::*;
+use bevy::image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor};
+use bevy::math::Affine2;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 use noise::{NoiseFn, Perlin};
+use std::f64::consts::TAU;
+use crate::world::GameConfig;
 
 pub struct SkyboxPlugin;
 
 impl Plugin for SkyboxPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_skybox);
+        app.add_systems(Startup, setup_skybox)
+            .add_systems(Update, (reseed_sky_texture, drift_clouds));
     }
 }
 
+#[derive(Resource)]
+struct SkyTexture {
+    handle: Handle<Image>,
+    cloud_handle: Handle<Image>,
+    seed: u32,
+}
+
+#[derive(Component)]
+pub struct CloudLayer {
+    pub offset: Vec2,
+    pub speed: Vec2,
+}
+
+pub const CLEAR_VISIBILITY: f32 = 200.0;
+pub const MAX_FOG_WIND: f32 = 4.0;
+
+pub fn fog_visibility(fog: &DistanceFog) -> f32 {
+    match fog.falloff {
+        FogFalloff::Linear { end, .. } => end,
+        FogFalloff::Exponential { density } => 3.0 / density,
+        FogFalloff::ExponentialSquared { density } => 3.0_f32.sqrt() / density,
+        FogFalloff::Atmospheric { extinction, .. } => 3.0 / extinction.max_element(),
+    }
+}
+
+pub fn fog_wind_factor(fog: Option<&DistanceFog>) -> f32 {
+    let visibility = fog.map_or(CLEAR_VISIBILITY, fog_visibility);
+    if visibility.is_finite() && visibility > 0.0 {
+        (CLEAR_VISIBILITY / visibility).clamp(1.0, MAX_FOG_WIND)
+    } else {
+        1.0
+    }
+}
+
+pub fn wrap_uv_offset(offset: Vec2) -> Vec2 {
+    let wrap = |value: f32| {
+        let wrapped = value.rem_euclid(1.0);
+        if wrapped >= 1.0 { 0.0 } else { wrapped }
+    };
+    Vec2::new(wrap(offset.x), wrap(offset.y))
+}
+
 fn setup_skybox(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut images: ResMut<Assets<Image>>,
+    game_config: Res<GameConfig>,
 ) {
-    let skybox_texture = generate_sky_texture();
+    let seed = game_config.seed as u32;
+    let skybox_texture = generate_sky_texture(seed);
     let texture_handle = images.add(skybox_texture);
+    let cloud_handle = images.add(generate_cloud_texture(seed));
+
+    commands.insert_resource(SkyTexture {
+        handle: texture_handle.clone(),
+        cloud_handle: cloud_handle.clone(),
+        seed,
+    });
 
     let skybox_material = materials.add(StandardMaterial {
         base_color_texture: Some(texture_handle),
@@ -33,11 +89,103 @@ fn setup_skybox(
         MeshMaterial3d(skybox_material),
         Transform::from_xyz(0.0, 0.0, 0.0).with_scale(Vec3::new(-1.0, 1.0, 1.0)),
     ));
+
+    let cloud_material = materials.add(StandardMaterial {
+        base_color_texture: Some(cloud_handle),
+        alpha_mode: AlphaMode::Blend,
+        unlit: true,
+        cull_mode: None,
+        ..default()
+    });
+
+    commands.spawn((
+        Mesh3d(meshes.add(Sphere::new(490.0).mesh().ico(5).unwrap())),
+        MeshMaterial3d(cloud_material),
+        Transform::from_xyz(0.0, 0.0, 0.0).with_scale(Vec3::new(-1.0, 1.0, 1.0)),
+        CloudLayer {
+            offset: Vec2::ZERO,
+            speed: Vec2::new(0.004, 0.0),
+        },
+    ));
+}
+
+fn drift_clouds(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    fog_query: Query<&DistanceFog, With<Camera3d>>,
+    mut cloud_query: Query<(&mut CloudLayer, &MeshMaterial3d<StandardMaterial>)>,
+) {
+    let wind = fog_wind_factor(fog_query.iter().next());
+
+    for (mut cloud_layer, material_handle) in cloud_query.iter_mut() {
+        cloud_layer.offset = wrap_uv_offset(cloud_layer.offset + cloud_layer.speed * wind * time.delta_secs());
+
+        if let Some(material) = materials.get_mut(&material_handle.0) {
+            material.uv_transform = Affine2::from_translation(cloud_layer.offset);
+        }
+    }
+}
+
+fn reseed_sky_texture(
+    game_config: Res<GameConfig>,
+    mut sky_texture: ResMut<SkyTexture>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let seed = game_config.seed as u32;
+    if sky_texture.seed == seed {
+        return;
+    }
+
+    sky_texture.seed = seed;
+    images.insert(&sky_texture.handle, generate_sky_texture(seed));
+    images.insert(&sky_texture.cloud_handle, generate_cloud_texture(seed));
+}
+
+pub fn generate_cloud_texture(seed: u32) -> Image {
+    let size = 512;
+    let perlin = Perlin::new(seed.wrapping_add(1));
+    let horizon = 0.5;
+
+    let mut data = Vec::with_capacity((size * size * 4) as usize);
+
+    for y in 0..size {
+        for x in 0..size {
+            let angle = x as f64 / size as f64 * TAU;
+            let ny = y as f64 / size as f64;
+
+            let noise_value = perlin.get([angle.cos() * 2.0, angle.sin() * 2.0, ny * 6.0]) * 0.5 + 0.5;
+            let coverage = ((noise_value - 0.55) / 0.3).clamp(0.0, 1.0);
+            let horizon_fade = ((ny - horizon) / 0.1).clamp(0.0, 1.0);
+            let alpha = (coverage * horizon_fade * 0.6) as f32;
+
+            data.extend_from_slice(&[255, 255, 255, (alpha * 255.0) as u8]);
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        Default::default(),
+    );
+
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..default()
+    });
+
+    image
 }
 
-fn generate_sky_texture() -> Image {
+pub fn generate_sky_texture(seed: u32) -> Image {
     let size = 512;
-    let perlin = Perlin::new(42);
+    let perlin = Perlin::new(seed);
     
     let mut data = Vec::with_capacity((size * size * 4) as usize);
 
@@ -90,3 +238,47 @@ fn generate_sky_texture() -> Image {
         Default::default(),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear_fog(end: f32) -> DistanceFog {
+        DistanceFog {
+            falloff: FogFalloff::Linear { start: 0.0, end },
+            ..default()
+        }
+    }
+
+    #[test]
+    fn uv_offset_wraps_into_unit_range() {
+        for offset in [Vec2::new(1.0, -1.0), Vec2::new(3.25, -0.25), Vec2::new(-1e-9, 0.999_999_9), Vec2::splat(-7.5)] {
+            let wrapped = wrap_uv_offset(offset);
+            assert!((0.0..1.0).contains(&wrapped.x) && (0.0..1.0).contains(&wrapped.y), "{offset} -> {wrapped}");
+        }
+        assert!(wrap_uv_offset(Vec2::new(3.25, -0.25)).abs_diff_eq(Vec2::new(0.25, 0.75), 1e-6));
+    }
+
+    #[test]
+    fn clouds_drift_faster_in_denser_fog() {
+        let clear = fog_wind_factor(None);
+        let light = fog_wind_factor(Some(&linear_fog(150.0)));
+        let dense = fog_wind_factor(Some(&linear_fog(60.0)));
+
+        assert_eq!(clear, 1.0);
+        assert!(clear < light && light < dense);
+        assert_eq!(fog_wind_factor(Some(&linear_fog(1.0))), MAX_FOG_WIND);
+        assert_eq!(fog_wind_factor(Some(&linear_fog(0.0))), 1.0);
+    }
+
+    #[test]
+    fn sky_texture_is_reproducible_per_seed() {
+        let sky = generate_sky_texture(7);
+        assert_eq!(sky.data, generate_sky_texture(7).data);
+        assert_ne!(sky.data, generate_sky_texture(8).data);
+
+        let clouds = generate_cloud_texture(7);
+        assert_eq!(clouds.data, generate_cloud_texture(7).data);
+        assert_ne!(clouds.data, generate_cloud_texture(8).data);
+    }
+}
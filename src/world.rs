@@ -1,14 +1,125 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
+use crate::physics::world_collision_groups;
 
 pub struct WorldPlugin;
 
 impl Plugin for WorldPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, (setup_lighting, spawn_checkerboard_floor, spawn_center_platform));
+        app.init_resource::<GameConfig>()
+            .init_resource::<WorldRules>()
+            .add_systems(Startup, (setup_lighting, setup_spawn_points, spawn_checkerboard_floor, spawn_center_platform));
     }
 }
 
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct GameConfig {
+    pub seed: u64,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self { seed: 12345 }
+    }
+}
+
+impl GameConfig {
+    pub fn randomize_seed(&mut self) {
+        use rand::Rng;
+        self.seed = rand::thread_rng().r#gen::<u32>() as u64;
+    }
+}
+
+pub fn parse_seed(input: &str) -> Option<u64> {
+    input.trim().parse().ok()
+}
+
+pub const FLOOR_TILE_SIZE: f32 = 2.0;
+pub const FLOOR_GRID_SIZE: i32 = 20;
+pub const PLATFORM_SIZE: Vec3 = Vec3::new(4.0, 1.0, 4.0);
+pub const PLATFORM_Y: f32 = 1.5;
+pub const SPAWN_CLEARANCE: f32 = 1.0;
+
+pub fn ground_height_at(x: f32, z: f32) -> Option<f32> {
+    let platform_half = PLATFORM_SIZE / 2.0;
+    if x.abs() <= platform_half.x && z.abs() <= platform_half.z {
+        return Some(PLATFORM_Y + platform_half.y);
+    }
+
+    let floor_min = -(FLOOR_GRID_SIZE as f32) * FLOOR_TILE_SIZE - FLOOR_TILE_SIZE / 2.0;
+    let floor_max = (FLOOR_GRID_SIZE - 1) as f32 * FLOOR_TILE_SIZE + FLOOR_TILE_SIZE / 2.0;
+    let on_floor = (floor_min..=floor_max).contains(&x) && (floor_min..=floor_max).contains(&z);
+    on_floor.then_some(0.0)
+}
+
+pub fn footprint_ground_height(x: f32, z: f32, radius: f32) -> Option<f32> {
+    let center = ground_height_at(x, z)?;
+    let level = [(radius, 0.0), (-radius, 0.0), (0.0, radius), (0.0, -radius)]
+        .into_iter()
+        .all(|(dx, dz)| ground_height_at(x + dx, z + dz) == Some(center));
+
+    level.then_some(center)
+}
+
+pub fn seeded_spawn_position(seed: u64, player_id: u32, radius: f32) -> Vec3 {
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    let mut rng = StdRng::seed_from_u64(seed ^ (player_id as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    let extent = (FLOOR_GRID_SIZE - 2) as f32 * FLOOR_TILE_SIZE;
+
+    loop {
+        let x = rng.gen_range(-extent..extent);
+        let z = rng.gen_range(-extent..extent);
+        if let Some(ground) = footprint_ground_height(x, z, radius) {
+            return Vec3::new(x, ground + SPAWN_CLEARANCE, z);
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct WorldRules {
+    pub death_y: f32,
+}
+
+impl Default for WorldRules {
+    fn default() -> Self {
+        Self { death_y: -20.0 }
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct SpawnPoints(pub Vec<Vec3>);
+
+impl SpawnPoints {
+    pub fn nearest(&self, position: Vec3) -> Option<Vec3> {
+        nearest_spawn(&self.0, position)
+    }
+}
+
+pub fn nearest_spawn(spawns: &[Vec3], position: Vec3) -> Option<Vec3> {
+    spawns.iter().copied().min_by(|a, b| {
+        let da = Vec2::new(a.x - position.x, a.z - position.z).length_squared();
+        let db = Vec2::new(b.x - position.x, b.z - position.z).length_squared();
+        da.total_cmp(&db)
+    })
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SurfaceKind {
+    #[default]
+    Checkerboard,
+    Platform,
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SurfaceIndex(pub u32);
+
+pub fn floor_tile_index(x: i32, z: i32) -> u32 {
+    let grid_size = FLOOR_GRID_SIZE;
+    ((x + grid_size) * grid_size * 2 + (z + grid_size)) as u32
+}
+
 fn setup_lighting(mut commands: Commands) {
     commands.insert_resource(AmbientLight {
         color: Color::WHITE,
@@ -22,13 +133,26 @@ fn setup_lighting(mut commands: Commands) {
     });
 }
 
+fn setup_spawn_points(mut commands: Commands) {
+    let corner = 30.0;
+    let spawn_y = 2.0;
+
+    commands.insert_resource(SpawnPoints(vec![
+        Vec3::new(0.0, spawn_y, 0.0),
+        Vec3::new(corner, spawn_y, corner),
+        Vec3::new(-corner, spawn_y, corner),
+        Vec3::new(corner, spawn_y, -corner),
+        Vec3::new(-corner, spawn_y, -corner),
+    ]));
+}
+
 fn spawn_checkerboard_floor(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    let tile_size = 2.0;
-    let grid_size = 20;
+    let tile_size = FLOOR_TILE_SIZE;
+    let grid_size = FLOOR_GRID_SIZE;
 
     let white_material = materials.add(StandardMaterial {
         base_color: Color::srgb(0.9, 0.9, 0.9),
@@ -57,6 +181,9 @@ fn spawn_checkerboard_floor(
                 Transform::from_xyz(x as f32 * tile_size, -0.1, z as f32 * tile_size),
                 RigidBody::Fixed,
                 Collider::cuboid(tile_size / 2.0, 0.1, tile_size / 2.0),
+                world_collision_groups(),
+                SurfaceKind::Checkerboard,
+                SurfaceIndex(floor_tile_index(x, z)),
             ));
         }
     }
@@ -67,10 +194,10 @@ fn spawn_center_platform(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
-    let platform_width = 4.0;
-    let platform_height = 1.0;
-    let platform_depth = 4.0;
-    let platform_y = 1.5;
+    let platform_width = PLATFORM_SIZE.x;
+    let platform_height = PLATFORM_SIZE.y;
+    let platform_depth = PLATFORM_SIZE.z;
+    let platform_y = PLATFORM_Y;
 
     let platform_material = materials.add(StandardMaterial {
         base_color: Color::srgb(0.3, 0.6, 0.8),
@@ -87,5 +214,53 @@ fn spawn_center_platform(
         Transform::from_xyz(0.0, platform_y, 0.0),
         RigidBody::Fixed,
         Collider::cuboid(platform_width / 2.0, platform_height / 2.0, platform_depth / 2.0),
+        world_collision_groups(),
+        SurfaceKind::Platform,
+        SurfaceIndex(0),
     ));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_parse_from_trimmed_digits_only() {
+        assert_eq!(parse_seed("42"), Some(42));
+        assert_eq!(parse_seed("  1234567890\n"), Some(1_234_567_890));
+        assert_eq!(parse_seed(""), None);
+        assert_eq!(parse_seed("-1"), None);
+        assert_eq!(parse_seed("abc"), None);
+    }
+
+    #[test]
+    fn nearest_spawn_ignores_height() {
+        let spawns = [Vec3::new(10.0, 0.0, 0.0), Vec3::new(0.0, 50.0, 2.0), Vec3::new(-5.0, 1.0, -5.0)];
+
+        assert_eq!(nearest_spawn(&spawns, Vec3::ZERO), Some(spawns[1]));
+        assert_eq!(nearest_spawn(&spawns, Vec3::new(9.0, -30.0, 0.0)), Some(spawns[0]));
+        assert_eq!(nearest_spawn(&[], Vec3::ZERO), None);
+    }
+
+    #[test]
+    fn seeded_spawns_stand_on_level_ground() {
+        let radius = 0.3;
+        for player_id in 0..32 {
+            let spawn = seeded_spawn_position(99, player_id, radius);
+            let ground = footprint_ground_height(spawn.x, spawn.z, radius).unwrap();
+            assert_eq!(spawn.y, ground + SPAWN_CLEARANCE);
+        }
+
+        assert_eq!(seeded_spawn_position(99, 3, radius), seeded_spawn_position(99, 3, radius));
+        assert_ne!(seeded_spawn_position(99, 3, radius), seeded_spawn_position(99, 4, radius));
+    }
+
+    #[test]
+    fn footprints_straddling_the_platform_edge_are_rejected() {
+        let edge = PLATFORM_SIZE.x / 2.0;
+        assert_eq!(footprint_ground_height(0.0, 0.0, 0.3), Some(PLATFORM_Y + PLATFORM_SIZE.y / 2.0));
+        assert_eq!(footprint_ground_height(edge, 0.0, 0.3), None);
+        assert_eq!(footprint_ground_height(edge + 5.0, 0.0, 0.3), Some(0.0));
+        assert_eq!(footprint_ground_height(1000.0, 0.0, 0.3), None);
+    }
+}